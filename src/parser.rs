@@ -1,9 +1,12 @@
 use std::{
     collections::HashMap,
-    fmt::{Debug, Formatter, Result},
+    fmt::{self, Debug, Display, Formatter},
+    ops::Range,
 };
 
-//// Standard keywords:
+use self::pattern::Pattern;
+
+// Standard keywords:
 /// Not in use:
 // _ (9), const, goto
 /// Packages:
@@ -34,7 +37,7 @@ use std::{
 // assert, break, continue, return, throw, yield (14)
 /// Operators:
 // instanceof, new
-//// Module keywords:
+// Module keywords:
 /// Not in use:
 // _
 /// Module:
@@ -47,70 +50,651 @@ use std::{
 // static, transitive
 /// Others:
 // to, with
+pub mod annotation;
+pub mod character;
+pub mod comment;
+pub mod identifier;
+pub mod javadoc;
+pub mod keyword;
+pub mod lexer;
 pub mod number;
+pub mod operator;
+pub mod pattern;
+pub mod string;
+pub mod text_block;
+pub mod unicode;
+pub mod whitespace;
+
+/// A location in the source. Lines and columns are zero-based; `column` counts
+/// bytes from the start of the line and `utf16_column` counts UTF-16 code units.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+    pub utf16_column: usize,
+}
+
+/// The byte range `start..end` a token covers, along with the positions of both ends.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+    pub start_pos: Position,
+    pub end_pos: Position,
+}
+
+impl Span {
+    /// The bytes the span covers.
+    pub fn range(&self) -> Range<usize> {
+        self.start..self.end
+    }
+}
+
+/// The kinds of problems the parsers report while still producing tokens.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DiagnosticKind {
+    IntNumberTooLarge,
+    FpNumberTooLarge,
+    FpNumberTooSmall,
+    IllegalUnderscore,
+    IllegalBinaryDigit,
+    IllegalOctalDigit,
+    InvalidBinaryNumber,
+    InvalidHexNumber,
+    MalformedFpLiteral,
+    IllegalCharacter,
+}
+
+impl DiagnosticKind {
+    /// The message `javac` reports for this kind of problem.
+    pub fn message(self) -> &'static str {
+        match self {
+            DiagnosticKind::IntNumberTooLarge => "integer number too large",
+            DiagnosticKind::FpNumberTooLarge => "floating-point number too large",
+            DiagnosticKind::FpNumberTooSmall => "floating-point number too small",
+            DiagnosticKind::IllegalUnderscore => "illegal underscore",
+            DiagnosticKind::IllegalBinaryDigit => "illegal digit in a binary literal",
+            DiagnosticKind::IllegalOctalDigit => "illegal digit in an octal literal",
+            DiagnosticKind::InvalidBinaryNumber => {
+                "binary numbers must contain at least one binary digit"
+            }
+            DiagnosticKind::InvalidHexNumber => {
+                "hexadecimal numbers must contain at least one hexadecimal digit"
+            }
+            DiagnosticKind::MalformedFpLiteral => "malformed floating-point literal",
+            DiagnosticKind::IllegalCharacter => "illegal character",
+        }
+    }
+}
+
+/// An edit that fixes a problem: replacing the bytes in `span` with `replacement`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Fix {
+    /// What the edit does, as shown to the user.
+    pub title: String,
+    pub span: Range<usize>,
+    pub replacement: String,
+}
+
+impl Fix {
+    pub fn new(title: &str, span: Range<usize>, replacement: String) -> Self {
+        Self {
+            title: String::from(title),
+            span,
+            replacement,
+        }
+    }
+}
+
+/// A problem with the code covered by a token. It starts where the token carrying it
+/// starts and spans `len` bytes, possibly covering the tokens that follow it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub kind: DiagnosticKind,
+    pub message: String,
+    pub len: usize,
+    pub span: Option<Span>,
+    /// A suggested fix, with its span relative to the start of the diagnostic.
+    pub fix: Option<Fix>,
+}
+
+impl Diagnostic {
+    pub fn new(kind: DiagnosticKind, message: String, len: usize) -> Self {
+        Self {
+            kind,
+            message,
+            len,
+            span: None,
+            fix: None,
+        }
+    }
+
+    pub fn with_fix(mut self, fix: Fix) -> Self {
+        self.fix = Some(fix);
+        self
+    }
+}
+
+/// What kind of error a parser reports.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ErrorCode {
+    /// The code does not start with a token the parser recognizes.
+    NoMatch,
+    /// The code starts with a token the parser recognizes, but it is malformed.
+    Malformed(DiagnosticKind),
+    /// The parser could not run, like when one of its patterns does not compile.
+    Internal,
+}
+
+/// Why a parser could not tokenize the start of some code, in a form tools such as
+/// quick-fixes can act on. Spans are byte ranges into the code that was parsed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    pub code: ErrorCode,
+    pub span: Range<usize>,
+    pub message: String,
+    /// The kinds of token that would have been recognized, for `NoMatch` errors.
+    pub expected: Vec<TokenKind>,
+    /// Boxed, as most errors have none, to keep `Result`s carrying errors small.
+    pub fix: Option<Box<Fix>>,
+}
+
+impl ParseError {
+    pub fn no_match(expected: Vec<TokenKind>) -> Self {
+        let message = match expected.as_slice() {
+            [] => String::from("no token recognized"),
+            _ => format!(
+                "expected {}",
+                expected
+                    .iter()
+                    .map(|k| k.name())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        };
+        Self {
+            code: ErrorCode::NoMatch,
+            span: 0..0,
+            message,
+            expected,
+            fix: None,
+        }
+    }
+
+    pub fn internal(message: String) -> Self {
+        Self {
+            code: ErrorCode::Internal,
+            span: 0..0,
+            message,
+            expected: vec![],
+            fix: None,
+        }
+    }
+
+    /// The error `diagnostic` describes, carried by a token starting `start` bytes into the code.
+    pub fn from_diagnostic(diagnostic: &Diagnostic, start: usize) -> Self {
+        Self {
+            code: ErrorCode::Malformed(diagnostic.kind),
+            span: start..start + diagnostic.len,
+            message: diagnostic.message.clone(),
+            expected: vec![],
+            fix: diagnostic.fix.clone().map(|fix| {
+                Box::new(Fix {
+                    span: start + fix.span.start..start + fix.span.end,
+                    ..fix
+                })
+            }),
+        }
+    }
+
+    /// The first problem diagnosed in `tokens`, which start at the beginning of the code.
+    pub fn from_tokens(tokens: &[Token]) -> Option<Self> {
+        let mut start = 0;
+        for token in tokens {
+            if let Some(diagnostic) = &token.diagnostic {
+                return Some(Self::from_diagnostic(diagnostic, start));
+            }
+            start += token.len;
+        }
+        None
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// A value attached to a token under a metadata key, typed like the Java value it holds.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Value {
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+}
+
+/// The parts of a number literal.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum NumberPart {
+    Sign,
+    Prefix,
+    Digits,
+    Dot,
+    Exponent,
+    ExponentSign,
+    ExponentPower,
+    Suffix,
+}
+
+/// The parts of a string, text block or character literal.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum QuotedPart {
+    Quote,
+    Content,
+    Escape,
+}
+
+/// The parts of a doc comment.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum DocPart {
+    Text,
+    /// A block tag like `@param`.
+    Tag,
+    /// The parameter name following `@param`.
+    Param,
+    /// The `{@name` and `}` delimiting an inline tag.
+    Inline,
+    /// The target of `{@link}`, `{@linkplain}`, `{@see}` and `{@value}`.
+    Link,
+    /// The body of `{@code}`, `{@literal}` and `{@snippet}`.
+    Code,
+    Html,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum CommentKind {
+    Line,
+    Block,
+    /// `TODO`, `FIXME` or `XXX` inside any kind of comment.
+    Tag,
+    Doc(DocPart),
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ModifierKind {
+    Field,
+    Access,
+    Restricting,
+    Method,
+    Other,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ModuleKeywordKind {
+    Module,
+    Modifier,
+    Relationship,
+    RelationshipModifier,
+    Other,
+}
+
+/// The parts of an annotation's name, `@org.junit.Test` for instance.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum AnnotationPart {
+    At,
+    Name,
+    Dot,
+}
+
+/// Operator categories, following JLS 3.12.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum OperatorKind {
+    /// `+ - * / % ++ --`
+    Arithmetic,
+    /// `& | ^ ~`
+    Bitwise,
+    /// `<< >> >>>`
+    Shift,
+    /// `== != < > <= >=`
+    Comparison,
+    /// `&& || !`
+    Logical,
+    /// `=` and the compound assignments like `>>>=`.
+    Assignment,
+    /// `?` and `:`, which also ends labels and `case` labels.
+    Conditional,
+    /// `->`
+    Arrow,
+}
+
+/// Separators, following JLS 3.11.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum SeparatorKind {
+    /// `(` or `)`
+    Paren,
+    /// `{` or `}`
+    Brace,
+    /// `[` or `]`
+    Bracket,
+    /// `<` or `>` around type arguments and type parameters.
+    Angle,
+    Semicolon,
+    Comma,
+    Dot,
+    /// `...`
+    Ellipsis,
+    At,
+    /// `::`
+    MethodReference,
+}
+
+/// The keyword categories listed at the top of this file.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum KeywordKind {
+    Unused,
+    Package,
+    Literal,
+    Type,
+    Reference,
+    Declaration,
+    Relationship,
+    Modifier(ModifierKind),
+    Control,
+    ControlFlow,
+    Operator,
+    /// `_` as an unnamed variable.
+    Unnamed,
+    Module(ModuleKeywordKind),
+}
+
+/// What a token is. Every kind also has a stable dotted name, like `number.e.sign`,
+/// for consumers that identify tokens by string.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum TokenKind {
+    Whitespace,
+    /// A single `\n`, `\r` or `\r\n`, with which one it is in the metadata.
+    LineTerminator,
+    Identifier,
+    Operator(OperatorKind),
+    Separator(SeparatorKind),
+    Keyword(KeywordKind),
+    Annotation(AnnotationPart),
+    Number(NumberPart),
+    String(QuotedPart),
+    Char(QuotedPart),
+    Comment(CommentKind),
+    /// Code that is recognized but malformed, like an unterminated string.
+    Invalid,
+    /// Code that no parser recognizes.
+    Unknown,
+}
+
+impl TokenKind {
+    pub fn name(self) -> &'static str {
+        use {AnnotationPart as A, OperatorKind as O, SeparatorKind as S};
+        use {CommentKind as C, KeywordKind as K, ModifierKind as M, ModuleKeywordKind as MK};
+        use {DocPart as D, NumberPart as N, QuotedPart as Q, TokenKind as T};
+        match self {
+            T::Whitespace => "whitespace",
+            T::LineTerminator => "line_terminator",
+            T::Identifier => "identifier",
+            T::Operator(O::Arithmetic) => "operator.arithmetic",
+            T::Operator(O::Bitwise) => "operator.bitwise",
+            T::Operator(O::Shift) => "operator.shift",
+            T::Operator(O::Comparison) => "operator.comparison",
+            T::Operator(O::Logical) => "operator.logical",
+            T::Operator(O::Assignment) => "operator.assignment",
+            T::Operator(O::Conditional) => "operator.conditional",
+            T::Operator(O::Arrow) => "operator.arrow",
+            T::Separator(S::Paren) => "separator.paren",
+            T::Separator(S::Brace) => "separator.brace",
+            T::Separator(S::Bracket) => "separator.bracket",
+            T::Separator(S::Angle) => "separator.angle",
+            T::Separator(S::Semicolon) => "separator.semicolon",
+            T::Separator(S::Comma) => "separator.comma",
+            T::Separator(S::Dot) => "separator.dot",
+            T::Separator(S::Ellipsis) => "separator.ellipsis",
+            T::Separator(S::At) => "separator.at",
+            T::Separator(S::MethodReference) => "separator.method_reference",
+            T::Keyword(K::Unused) => "keyword.unused",
+            T::Keyword(K::Package) => "keyword.package",
+            T::Keyword(K::Literal) => "keyword.literal",
+            T::Keyword(K::Type) => "keyword.type",
+            T::Keyword(K::Reference) => "keyword.reference",
+            T::Keyword(K::Declaration) => "keyword.declaration",
+            T::Keyword(K::Relationship) => "keyword.relationship",
+            T::Keyword(K::Modifier(M::Field)) => "keyword.modifier.field",
+            T::Keyword(K::Modifier(M::Access)) => "keyword.modifier.access",
+            T::Keyword(K::Modifier(M::Restricting)) => "keyword.modifier.restricting",
+            T::Keyword(K::Modifier(M::Method)) => "keyword.modifier.method",
+            T::Keyword(K::Modifier(M::Other)) => "keyword.modifier",
+            T::Keyword(K::Control) => "keyword.control",
+            T::Keyword(K::ControlFlow) => "keyword.control.flow",
+            T::Keyword(K::Operator) => "keyword.operator",
+            T::Keyword(K::Unnamed) => "keyword.unnamed",
+            T::Keyword(K::Module(MK::Module)) => "keyword.module",
+            T::Keyword(K::Module(MK::Modifier)) => "keyword.module.modifier",
+            T::Keyword(K::Module(MK::Relationship)) => "keyword.module.relationship",
+            T::Keyword(K::Module(MK::RelationshipModifier)) => {
+                "keyword.module.relationship.modifier"
+            }
+            T::Keyword(K::Module(MK::Other)) => "keyword.module.other",
+            T::Annotation(A::At) => "annotation.at",
+            T::Annotation(A::Name) => "annotation.name",
+            T::Annotation(A::Dot) => "annotation.dot",
+            T::Number(N::Sign) => "number.sign",
+            T::Number(N::Prefix) => "number.prefix",
+            T::Number(N::Digits) => "number",
+            T::Number(N::Dot) => "number.dot",
+            T::Number(N::Exponent) => "number.e",
+            T::Number(N::ExponentSign) => "number.e.sign",
+            T::Number(N::ExponentPower) => "number.e.power",
+            T::Number(N::Suffix) => "number.suffix",
+            T::String(Q::Quote) => "string.quote",
+            T::String(Q::Content) => "string",
+            T::String(Q::Escape) => "string.escape",
+            T::Char(Q::Quote) => "char.quote",
+            T::Char(Q::Content) => "char",
+            T::Char(Q::Escape) => "char.escape",
+            T::Comment(C::Line) => "comment.line",
+            T::Comment(C::Block) => "comment.block",
+            T::Comment(C::Tag) => "comment.tag",
+            T::Comment(C::Doc(D::Text)) => "comment.doc",
+            T::Comment(C::Doc(D::Tag)) => "comment.doc.tag",
+            T::Comment(C::Doc(D::Param)) => "comment.doc.param",
+            T::Comment(C::Doc(D::Inline)) => "comment.doc.inline",
+            T::Comment(C::Doc(D::Link)) => "comment.doc.link",
+            T::Comment(C::Doc(D::Code)) => "comment.doc.code",
+            T::Comment(C::Doc(D::Html)) => "comment.doc.html",
+            T::Invalid => "invalid",
+            T::Unknown => "unknown",
+        }
+    }
+
+    /// The kind with the given dotted name, the inverse of `name`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        use {AnnotationPart as A, OperatorKind as O, SeparatorKind as S};
+        use {CommentKind as C, KeywordKind as K, ModifierKind as M, ModuleKeywordKind as MK};
+        use {DocPart as D, NumberPart as N, QuotedPart as Q, TokenKind as T};
+        Some(match name {
+            "whitespace" => T::Whitespace,
+            "line_terminator" => T::LineTerminator,
+            "identifier" => T::Identifier,
+            "operator.arithmetic" => T::Operator(O::Arithmetic),
+            "operator.bitwise" => T::Operator(O::Bitwise),
+            "operator.shift" => T::Operator(O::Shift),
+            "operator.comparison" => T::Operator(O::Comparison),
+            "operator.logical" => T::Operator(O::Logical),
+            "operator.assignment" => T::Operator(O::Assignment),
+            "operator.conditional" => T::Operator(O::Conditional),
+            "operator.arrow" => T::Operator(O::Arrow),
+            "separator.paren" => T::Separator(S::Paren),
+            "separator.brace" => T::Separator(S::Brace),
+            "separator.bracket" => T::Separator(S::Bracket),
+            "separator.angle" => T::Separator(S::Angle),
+            "separator.semicolon" => T::Separator(S::Semicolon),
+            "separator.comma" => T::Separator(S::Comma),
+            "separator.dot" => T::Separator(S::Dot),
+            "separator.ellipsis" => T::Separator(S::Ellipsis),
+            "separator.at" => T::Separator(S::At),
+            "separator.method_reference" => T::Separator(S::MethodReference),
+            "keyword.unused" => T::Keyword(K::Unused),
+            "keyword.package" => T::Keyword(K::Package),
+            "keyword.literal" => T::Keyword(K::Literal),
+            "keyword.type" => T::Keyword(K::Type),
+            "keyword.reference" => T::Keyword(K::Reference),
+            "keyword.declaration" => T::Keyword(K::Declaration),
+            "keyword.relationship" => T::Keyword(K::Relationship),
+            "keyword.modifier.field" => T::Keyword(K::Modifier(M::Field)),
+            "keyword.modifier.access" => T::Keyword(K::Modifier(M::Access)),
+            "keyword.modifier.restricting" => T::Keyword(K::Modifier(M::Restricting)),
+            "keyword.modifier.method" => T::Keyword(K::Modifier(M::Method)),
+            "keyword.modifier" => T::Keyword(K::Modifier(M::Other)),
+            "keyword.control" => T::Keyword(K::Control),
+            "keyword.control.flow" => T::Keyword(K::ControlFlow),
+            "keyword.operator" => T::Keyword(K::Operator),
+            "keyword.unnamed" => T::Keyword(K::Unnamed),
+            "keyword.module" => T::Keyword(K::Module(MK::Module)),
+            "keyword.module.modifier" => T::Keyword(K::Module(MK::Modifier)),
+            "keyword.module.relationship" => T::Keyword(K::Module(MK::Relationship)),
+            "keyword.module.relationship.modifier" => {
+                T::Keyword(K::Module(MK::RelationshipModifier))
+            }
+            "keyword.module.other" => T::Keyword(K::Module(MK::Other)),
+            "annotation.at" => T::Annotation(A::At),
+            "annotation.name" => T::Annotation(A::Name),
+            "annotation.dot" => T::Annotation(A::Dot),
+            "number.sign" => T::Number(N::Sign),
+            "number.prefix" => T::Number(N::Prefix),
+            "number" => T::Number(N::Digits),
+            "number.dot" => T::Number(N::Dot),
+            "number.e" => T::Number(N::Exponent),
+            "number.e.sign" => T::Number(N::ExponentSign),
+            "number.e.power" => T::Number(N::ExponentPower),
+            "number.suffix" => T::Number(N::Suffix),
+            "string.quote" => T::String(Q::Quote),
+            "string" => T::String(Q::Content),
+            "string.escape" => T::String(Q::Escape),
+            "char.quote" => T::Char(Q::Quote),
+            "char" => T::Char(Q::Content),
+            "char.escape" => T::Char(Q::Escape),
+            "comment.line" => T::Comment(C::Line),
+            "comment.block" => T::Comment(C::Block),
+            "comment.tag" => T::Comment(C::Tag),
+            "comment.doc" => T::Comment(C::Doc(D::Text)),
+            "comment.doc.tag" => T::Comment(C::Doc(D::Tag)),
+            "comment.doc.param" => T::Comment(C::Doc(D::Param)),
+            "comment.doc.inline" => T::Comment(C::Doc(D::Inline)),
+            "comment.doc.link" => T::Comment(C::Doc(D::Link)),
+            "comment.doc.code" => T::Comment(C::Doc(D::Code)),
+            "comment.doc.html" => T::Comment(C::Doc(D::Html)),
+            "invalid" => T::Invalid,
+            "unknown" => T::Unknown,
+            _ => return None,
+        })
+    }
+}
+
+impl Display for TokenKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
 
 pub struct Token {
-    pub name: String,
+    pub kind: TokenKind,
     pub len: usize,
-    pub metadata: HashMap<String, i32>,
+    pub metadata: HashMap<String, Value>,
+    pub span: Option<Span>,
+    pub diagnostic: Option<Diagnostic>,
 }
 
 impl Debug for Token {
-    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         f.debug_struct("Token")
-            .field("name", &self.name)
+            .field("kind", &self.kind)
             .field("len", &self.len)
             .field("metadata", &self.metadata)
+            .field("span", &self.span)
+            .field("diagnostic", &self.diagnostic)
             .finish()
     }
 }
 
 impl Token {
-    pub fn new(name: &str, len: usize) -> Self {
-        Self::with_meta(name, len, HashMap::new())
+    pub fn new(kind: TokenKind, len: usize) -> Self {
+        Self::with_meta(kind, len, HashMap::new())
     }
 
     pub fn whitespace(len: usize) -> Self {
-        Self::new("whitespace", len)
+        Self::new(TokenKind::Whitespace, len)
+    }
+
+    pub fn invalid(len: usize) -> Self {
+        Self::new(TokenKind::Invalid, len)
+    }
+
+    pub fn unknown(len: usize) -> Self {
+        Self::new(TokenKind::Unknown, len)
     }
 
     pub fn number_dot() -> Self {
-        Self::new("number.dot", 1)
+        Self::new(TokenKind::Number(NumberPart::Dot), 1)
     }
 
     pub fn number_sign() -> Self {
-        Self::new("number.sign", 1)
+        Self::new(TokenKind::Number(NumberPart::Sign), 1)
     }
 
     pub fn number_suffix() -> Self {
-        Self::new("number.suffix", 1)
+        Self::new(TokenKind::Number(NumberPart::Suffix), 1)
     }
 
     pub fn number_e() -> Self {
-        Self::new("number.e", 1)
+        Self::new(TokenKind::Number(NumberPart::Exponent), 1)
     }
 
     pub fn number_e_sign() -> Self {
-        Self::new("number.e.sign", 1)
+        Self::new(TokenKind::Number(NumberPart::ExponentSign), 1)
     }
 
-    pub fn with_meta(name: &str, len: usize, metadata: HashMap<String, i32>) -> Self {
+    pub fn with_meta(kind: TokenKind, len: usize, metadata: HashMap<String, Value>) -> Self {
         Self {
-            name: String::from(name),
+            kind,
             len,
             metadata,
+            span: None,
+            diagnostic: None,
         }
     }
 
-    pub fn add_metadata(&mut self, addition: HashMap<String, i32>) -> &Self {
+    /// The dotted name of the token's kind.
+    pub fn name(&self) -> &'static str {
+        self.kind.name()
+    }
+
+    pub fn add_metadata(&mut self, addition: HashMap<String, Value>) -> &Self {
         self.metadata.extend(addition);
         self
     }
+
+    pub fn with_diagnostic(mut self, diagnostic: Diagnostic) -> Self {
+        self.diagnostic = Some(diagnostic);
+        self
+    }
 }
 
 impl PartialEq for Token {
     fn eq(&self, other: &Self) -> bool {
-        self.name.eq(&other.name) && self.len == other.len && self.metadata.eq(&other.metadata)
+        self.kind == other.kind
+            && self.len == other.len
+            && self.metadata.eq(&other.metadata)
+            && self.span == other.span
+            && self.diagnostic == other.diagnostic
     }
 }
 
@@ -132,17 +716,286 @@ impl PartialEq for Token {
 //    }
 //}
 
-#[derive(Copy, Clone)]
+/// The newest Java release the parsers know about, used when none is set.
+pub const LATEST_RELEASE: u8 = 21;
+
+/// Language features whose syntax depends on the Java release being targeted.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Feature {
+    /// `_` is a keyword instead of an identifier.
+    UnderscoreKeyword,
+    /// `module-info.java` and its module keywords.
+    Modules,
+    /// `var` for local variable types.
+    LocalVariableTypeInference,
+    /// `yield` in switch expressions.
+    SwitchExpressions,
+    /// `"""` text blocks and the `\s` escape.
+    TextBlocks,
+    /// `record` declarations.
+    Records,
+    /// `sealed`, `non-sealed` and `permits`.
+    SealedClasses,
+    /// `_` as an unnamed variable or pattern.
+    UnnamedVariables,
+}
+
+impl Feature {
+    /// The release the feature became standard in, and the release it was first
+    /// available in as a preview feature.
+    fn releases(self) -> (u8, Option<u8>) {
+        match self {
+            Feature::UnderscoreKeyword | Feature::Modules => (9, None),
+            Feature::LocalVariableTypeInference => (10, None),
+            Feature::SwitchExpressions => (14, Some(13)),
+            Feature::TextBlocks => (15, Some(13)),
+            Feature::Records => (16, Some(14)),
+            Feature::SealedClasses => (17, Some(15)),
+            Feature::UnnamedVariables => (22, Some(21)),
+        }
+    }
+}
+
+/// Positional state the lexer carries from one token to the next, used to tell
+/// contextual keywords apart from identifiers, along with the targeted Java release.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub struct ParserContext {
     //modifiers: Modifiers,
+    release: u8,
+    preview: bool,
+    module_info: bool,
+    statement_start: bool,
+    type_declaration_head: bool,
+    after_dot: bool,
+    after_annotation: bool,
+    after_control: bool,
+    header_parens: usize,
+    annotation_parens: usize,
+    signed_numbers: bool,
+    after_operand: bool,
+    after_unary_minus: bool,
+    after_lower_case_name: bool,
+    generic_depth: usize,
+    line_start: bool,
 }
 
 impl ParserContext {
     pub fn new() -> Self {
-        Self {}
+        Self {
+            release: LATEST_RELEASE,
+            preview: false,
+            module_info: false,
+            statement_start: true,
+            type_declaration_head: false,
+            after_dot: false,
+            after_annotation: false,
+            after_control: false,
+            header_parens: 0,
+            annotation_parens: 0,
+            signed_numbers: false,
+            after_operand: false,
+            after_unary_minus: false,
+            after_lower_case_name: false,
+            generic_depth: 0,
+            line_start: true,
+        }
+    }
+
+    /// Targets the given Java release, as passed to `javac --release`.
+    pub fn with_release(mut self, release: u8) -> Self {
+        self.release = release;
+        self
+    }
+
+    /// Enables the preview features of the targeted release, as `javac --enable-preview` does.
+    pub fn with_preview(mut self, preview: bool) -> Self {
+        self.preview = preview;
+        self
+    }
+
+    pub fn release(&self) -> u8 {
+        self.release
+    }
+
+    pub fn is_preview(&self) -> bool {
+        self.preview
+    }
+
+    /// Whether `feature` can be used in the targeted release. With preview enabled,
+    /// features count as available from the release they were first previewed in.
+    pub fn supports(&self, feature: Feature) -> bool {
+        let (standard, preview) = feature.releases();
+        self.release >= standard || (self.preview && preview.is_some_and(|p| self.release >= p))
+    }
+
+    /// Marks the code as a `module-info.java` compilation unit, enabling module keywords.
+    pub fn with_module_info(mut self, module_info: bool) -> Self {
+        self.module_info = module_info;
+        self
+    }
+
+    pub fn is_module_info(&self) -> bool {
+        self.module_info
+    }
+
+    /// Makes number literals include a leading `+` or `-` sign as a `number.sign` token.
+    /// By default the sign is a separate operator, as it is in the Java grammar.
+    pub fn with_signed_numbers(mut self, signed_numbers: bool) -> Self {
+        self.signed_numbers = signed_numbers;
+        self
+    }
+
+    pub fn is_signed_numbers(&self) -> bool {
+        self.signed_numbers
+    }
+
+    /// Whether the previous token was a unary `-`, the only place the literals
+    /// `2147483648` and `9223372036854775808L` may appear in.
+    pub fn is_after_unary_minus(&self) -> bool {
+        self.after_unary_minus
+    }
+
+    /// Whether the previous token was an identifier starting with a lower case letter,
+    /// which by convention names a variable or method rather than a type.
+    pub fn is_after_lower_case_name(&self) -> bool {
+        self.after_lower_case_name
+    }
+
+    /// How many type argument or type parameter lists the next token is nested in.
+    /// Inside them, `>>` and `>>>` close several lists instead of being shifts.
+    pub fn generic_depth(&self) -> usize {
+        self.generic_depth
+    }
+
+    /// Whether only whitespace precedes the next token on its line.
+    pub fn is_line_start(&self) -> bool {
+        self.line_start
+    }
+
+    /// Whether the next token starts a statement or a member declaration. Modifiers
+    /// and annotations, arguments included, do not end this position.
+    pub fn is_statement_start(&self) -> bool {
+        self.statement_start
+    }
+
+    /// Whether the next token is between a type declaration keyword and the body.
+    pub fn is_type_declaration_head(&self) -> bool {
+        self.type_declaration_head
+    }
+
+    /// Whether the previous token was a `.`, so the next word is part of a qualified name.
+    pub fn is_after_dot(&self) -> bool {
+        self.after_dot
+    }
+
+    /// Updates the positional state after `token`, whose source text is `text`.
+    pub fn advance(&mut self, token: &Token, text: &str) {
+        self.line_start = match token.kind {
+            TokenKind::LineTerminator => true,
+            TokenKind::Whitespace => self.line_start,
+            _ => false,
+        };
+        if matches!(
+            token.kind,
+            TokenKind::Whitespace | TokenKind::LineTerminator | TokenKind::Comment(_)
+        ) {
+            return;
+        }
+        let operator = if matches!(token.kind, TokenKind::Operator(_) | TokenKind::Separator(_)) {
+            text
+        } else {
+            ""
+        };
+        // A `-` is unary unless it follows something that ends an operand.
+        self.after_unary_minus = operator == "-" && !self.after_operand;
+        self.after_lower_case_name =
+            token.kind == TokenKind::Identifier && text.starts_with(|c: char| c.is_lowercase());
+        self.after_operand = match token.kind {
+            TokenKind::Identifier
+            | TokenKind::Number(_)
+            | TokenKind::String(_)
+            | TokenKind::Char(_)
+            | TokenKind::Keyword(KeywordKind::Literal | KeywordKind::Reference) => true,
+            _ => matches!(operator, ")" | "]" | "++" | "--"),
+        };
+        if token.kind == TokenKind::Separator(SeparatorKind::Angle) {
+            match operator {
+                "<" => self.generic_depth += 1,
+                _ => self.generic_depth = self.generic_depth.saturating_sub(1),
+            }
+        } else if matches!(operator, ";" | "{" | "}") {
+            // Type arguments never span these, recover from a misread `<`.
+            self.generic_depth = 0;
+        }
+        if self.annotation_parens > 0 || (self.after_annotation && operator == "(") {
+            match operator {
+                "(" => self.annotation_parens += 1,
+                ")" => self.annotation_parens -= 1,
+                _ => {}
+            }
+            self.after_annotation = false;
+            return;
+        }
+        self.after_annotation = token.kind == TokenKind::Annotation(AnnotationPart::Name);
+        let modifier = matches!(
+            token.kind,
+            TokenKind::Keyword(KeywordKind::Modifier(_)) | TokenKind::Annotation(_)
+        );
+        if modifier {
+            self.after_dot = false;
+            return;
+        }
+        // `Foo.class` is a class literal rather than the start of a declaration.
+        if token.kind == TokenKind::Keyword(KeywordKind::Declaration) && !self.after_dot {
+            self.type_declaration_head = true;
+        } else if matches!(operator, ";" | "{") {
+            self.type_declaration_head = false;
+        }
+        self.after_dot = operator == ".";
+        // The statement after the header of an `if`, `while` or `for` may go without braces.
+        let header_end = self.header_parens == 1 && operator == ")";
+        match operator {
+            "(" if self.after_control || self.header_parens > 0 => self.header_parens += 1,
+            ")" if self.header_parens > 0 => self.header_parens -= 1,
+            _ => {}
+        }
+        let control = token.kind == TokenKind::Keyword(KeywordKind::Control);
+        self.after_control = control && matches!(text, "if" | "while" | "for");
+        self.statement_start = matches!(operator, ";" | "{" | "}" | ":")
+            || header_end
+            || (control && matches!(text, "else" | "do"));
+    }
+}
+
+impl Default for ParserContext {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
 pub trait Parser {
     fn parse(&self, code: &str, context: ParserContext) -> Option<Vec<Token>>;
+
+    /// The kinds of token this parser recognizes, reported when it does not match.
+    fn expected(&self) -> Vec<TokenKind> {
+        vec![]
+    }
+
+    /// The patterns `parse` matches code against, including those of any sub-parsers.
+    fn patterns(&self) -> Vec<&'static Pattern> {
+        vec![]
+    }
+
+    /// Like `parse`, but tells why no usable tokens were found: `Internal` when one of
+    /// the parser's patterns does not compile, `NoMatch` when the code does not start
+    /// with one of its tokens, or else the first problem diagnosed in the tokens found.
+    fn try_parse(&self, code: &str, context: ParserContext) -> Result<Vec<Token>, ParseError> {
+        if let Some(error) = self.patterns().iter().find_map(|p| p.error()) {
+            return Err(ParseError::internal(error.to_string()));
+        }
+        let tokens = self
+            .parse(code, context)
+            .ok_or_else(|| ParseError::no_match(self.expected()))?;
+        ParseError::from_tokens(&tokens).map_or(Ok(tokens), Err)
+    }
 }
@@ -0,0 +1,196 @@
+use lazy_static::lazy_static;
+
+use crate::parser::{
+    identifier::{IDENTIFIER_PART, IDENTIFIER_START},
+    pattern::Pattern,
+    whitespace::whitespace,
+    AnnotationPart, KeywordKind, Parser, ParserContext, Token, TokenKind,
+};
+
+lazy_static! {
+    static ref NAME_REGEX: Pattern =
+        Pattern::new(&format!("^[{}][{}]*", IDENTIFIER_START, IDENTIFIER_PART));
+    static ref QUALIFIER_REGEX: Pattern = Pattern::new(&format!(
+        r"^([ \t\x0C\r\n]*)\.([ \t\x0C\r\n]*)([{}][{}]*)",
+        IDENTIFIER_START, IDENTIFIER_PART
+    ));
+    static ref WHITESPACE_REGEX: Pattern = Pattern::new(r"^[ \t\x0C\r\n]*");
+}
+
+/// Tokenizes an annotation's `@` and its possibly qualified name, leaving any
+/// arguments to the other parsers. The JLS allows whitespace after the `@`.
+///
+/// `@interface` starts an annotation type declaration instead, and is tokenized as a
+/// declaration keyword.
+pub struct AnnotationParser;
+
+impl AnnotationParser {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for AnnotationParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Parser for AnnotationParser {
+    fn parse(&self, code: &str, _: ParserContext) -> Option<Vec<Token>> {
+        let rest = code.strip_prefix('@')?;
+        let space = WHITESPACE_REGEX.find(rest).map_or(0, |m| m.end());
+        let name = NAME_REGEX.find(&rest[space..])?.as_str();
+        let declaration = TokenKind::Keyword(KeywordKind::Declaration);
+        if name == "interface" {
+            return Some(match space {
+                0 => vec![Token::new(declaration, 10)],
+                _ => {
+                    let mut tokens = vec![Token::new(declaration, 1)];
+                    tokens.extend(whitespace(&rest[..space]));
+                    tokens.push(Token::new(declaration, name.len()));
+                    tokens
+                }
+            });
+        }
+        let mut tokens = vec![Token::new(TokenKind::Annotation(AnnotationPart::At), 1)];
+        tokens.extend(whitespace(&rest[..space]));
+        tokens.push(Token::new(
+            TokenKind::Annotation(AnnotationPart::Name),
+            name.len(),
+        ));
+        let mut offset = 1 + space + name.len();
+        while let Some(qualifier) = QUALIFIER_REGEX.captures(&code[offset..]) {
+            let (before, after, name) = (&qualifier[1], &qualifier[2], &qualifier[3]);
+            tokens.extend(whitespace(before));
+            tokens.push(Token::new(TokenKind::Annotation(AnnotationPart::Dot), 1));
+            tokens.extend(whitespace(after));
+            tokens.push(Token::new(
+                TokenKind::Annotation(AnnotationPart::Name),
+                name.len(),
+            ));
+            offset += qualifier[0].len();
+        }
+        Some(tokens)
+    }
+
+    fn patterns(&self) -> Vec<&'static Pattern> {
+        vec![&NAME_REGEX, &QUALIFIER_REGEX, &WHITESPACE_REGEX]
+    }
+
+    fn expected(&self) -> Vec<TokenKind> {
+        vec![TokenKind::Annotation(AnnotationPart::At)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{lexer::Lexer, Parser, ParserContext, Token, Value};
+    use super::*;
+    use crate::hashmap;
+
+    fn names(code: &str) -> Vec<(&'static str, usize)> {
+        Lexer::new()
+            .lex(code, ParserContext::new())
+            .iter()
+            .map(|t| (t.name(), t.len))
+            .collect()
+    }
+
+    #[test]
+    fn annotation_parser() {
+        let p = AnnotationParser::new();
+        assert_eq!(
+            Some(vec![
+                Token::new(TokenKind::Annotation(AnnotationPart::At), 1),
+                Token::new(TokenKind::Annotation(AnnotationPart::Name), 8),
+            ]),
+            p.parse("@Override\nvoid", ParserContext::new())
+        );
+        assert_eq!(
+            Some(vec![
+                Token::new(TokenKind::Annotation(AnnotationPart::At), 1),
+                Token::whitespace(1),
+                Token::new(TokenKind::Annotation(AnnotationPart::Name), 3),
+                Token::new(TokenKind::Annotation(AnnotationPart::Dot), 1),
+                Token::new(TokenKind::Annotation(AnnotationPart::Name), 5),
+                Token::whitespace(1),
+                Token::new(TokenKind::Annotation(AnnotationPart::Dot), 1),
+                Token::new(TokenKind::Annotation(AnnotationPart::Name), 4),
+            ]),
+            p.parse("@ org.junit .Test()", ParserContext::new())
+        );
+        assert_eq!(
+            Some(vec![Token::new(
+                TokenKind::Keyword(KeywordKind::Declaration),
+                10
+            )]),
+            p.parse("@interface A", ParserContext::new())
+        );
+        assert_eq!(
+            Some(vec![
+                Token::new(TokenKind::Keyword(KeywordKind::Declaration), 1),
+                Token::with_meta(
+                    TokenKind::LineTerminator,
+                    1,
+                    hashmap!(String::from("lf"), Value::Int(1))
+                ),
+                Token::whitespace(1),
+                Token::new(TokenKind::Keyword(KeywordKind::Declaration), 9),
+            ]),
+            p.parse("@\n interface A", ParserContext::new())
+        );
+        assert_eq!(None, p.parse("@ 1", ParserContext::new()));
+        // Only Java white space may follow the `@`, not a vertical tab.
+        assert_eq!(None, p.parse("@\x0BOverride", ParserContext::new()));
+        assert_eq!(None, p.parse("Override", ParserContext::new()));
+    }
+
+    #[test]
+    fn annotations() {
+        assert_eq!(
+            vec![
+                ("annotation.at", 1),
+                ("annotation.name", 16),
+                ("separator.paren", 1),
+                ("string.quote", 1),
+                ("string", 9),
+                ("string.quote", 1),
+                ("separator.paren", 1),
+                ("whitespace", 1),
+                ("keyword.modifier.restricting", 6),
+                ("whitespace", 1),
+                ("keyword.declaration", 5),
+            ],
+            names("@SuppressWarnings(\"unchecked\") sealed class")
+        );
+        assert_eq!(
+            vec![
+                ("annotation.at", 1),
+                ("annotation.name", 1),
+                ("separator.paren", 1),
+                ("identifier", 1),
+                ("whitespace", 1),
+                ("operator.assignment", 1),
+                ("whitespace", 1),
+                ("separator.paren", 1),
+                ("number", 1),
+                ("separator.paren", 1),
+                ("separator.paren", 1),
+                ("whitespace", 1),
+                ("keyword.modifier.restricting", 6),
+                ("whitespace", 1),
+                ("keyword.declaration", 5),
+            ],
+            names("@A(x = (1)) sealed class")
+        );
+        assert_eq!(
+            vec![
+                ("keyword.declaration", 10),
+                ("whitespace", 1),
+                ("identifier", 1),
+            ],
+            names("@interface A")
+        );
+    }
+}
@@ -0,0 +1,145 @@
+use crate::parser::{
+    pattern::Pattern,
+    string::{literal_escape, ESCAPE_REGEX},
+    Parser, ParserContext, QuotedPart, Token, TokenKind,
+};
+
+pub struct CharacterParser;
+
+impl CharacterParser {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for CharacterParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Parser for CharacterParser {
+    fn parse(&self, code: &str, context: ParserContext) -> Option<Vec<Token>> {
+        if !code.starts_with('\'') {
+            return None;
+        }
+        let mut tokens = vec![Token::new(TokenKind::Char(QuotedPart::Quote), 1)];
+        let mut offset = 1;
+        // Number of UTF-16 code units the literal denotes, an escape counts as one.
+        let mut units = 0;
+        loop {
+            let rest = &code[offset..];
+            let token = match rest.chars().next() {
+                Some('\'') => {
+                    offset += 1;
+                    if units != 1 || tokens.iter().any(|t| t.kind == TokenKind::Invalid) {
+                        return Some(vec![Token::invalid(offset)]);
+                    }
+                    tokens.push(Token::new(TokenKind::Char(QuotedPart::Quote), 1));
+                    return Some(tokens);
+                }
+                Some('\\') => literal_escape(rest, TokenKind::Char(QuotedPart::Escape), context),
+                Some('\r') | Some('\n') | None => {
+                    // Unterminated, the line terminator is left for the next token.
+                    tokens[0] = Token::invalid(1);
+                    return Some(tokens);
+                }
+                Some(c) => {
+                    units += c.len_utf16();
+                    Token::new(TokenKind::Char(QuotedPart::Content), c.len_utf8())
+                }
+            };
+            if token.kind != TokenKind::Char(QuotedPart::Content) {
+                units += 1;
+            }
+            offset += token.len;
+            tokens.push(token);
+        }
+    }
+
+    fn patterns(&self) -> Vec<&'static Pattern> {
+        vec![&ESCAPE_REGEX]
+    }
+
+    fn expected(&self) -> Vec<TokenKind> {
+        vec![TokenKind::Char(QuotedPart::Quote)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{Parser, ParserContext, Token};
+    use super::*;
+
+    #[test]
+    fn character_parser() {
+        let p = CharacterParser::new();
+        assert_eq!(
+            Some(vec![
+                Token::new(TokenKind::Char(QuotedPart::Quote), 1),
+                Token::new(TokenKind::Char(QuotedPart::Content), 1),
+                Token::new(TokenKind::Char(QuotedPart::Quote), 1),
+            ]),
+            p.parse("'a')", ParserContext::new())
+        );
+        assert_eq!(
+            Some(vec![
+                Token::new(TokenKind::Char(QuotedPart::Quote), 1),
+                Token::new(TokenKind::Char(QuotedPart::Content), 2),
+                Token::new(TokenKind::Char(QuotedPart::Quote), 1),
+            ]),
+            p.parse("'ж'", ParserContext::new())
+        );
+        for (code, len) in &[(r"'\n'", 2), (r"'\''", 2), (r"'\377'", 4), (r"'\7'", 2)] {
+            assert_eq!(
+                Some(vec![
+                    Token::new(TokenKind::Char(QuotedPart::Quote), 1),
+                    Token::new(TokenKind::Char(QuotedPart::Escape), *len),
+                    Token::new(TokenKind::Char(QuotedPart::Quote), 1),
+                ]),
+                p.parse(code, ParserContext::new())
+            );
+        }
+        assert_eq!(None, p.parse("\"a\"", ParserContext::new()));
+    }
+
+    #[test]
+    fn invalid_characters() {
+        let p = CharacterParser::new();
+        assert_eq!(
+            Some(vec![Token::invalid(2)]),
+            p.parse("''", ParserContext::new())
+        );
+        assert_eq!(
+            Some(vec![Token::invalid(4)]),
+            p.parse("'ab'", ParserContext::new())
+        );
+        assert_eq!(
+            Some(vec![Token::invalid(6)]),
+            p.parse(r"'\n\t'", ParserContext::new())
+        );
+        assert_eq!(
+            Some(vec![Token::invalid(4)]),
+            p.parse(r"'\q'", ParserContext::new())
+        );
+        assert_eq!(
+            Some(vec![Token::invalid(6)]),
+            p.parse("'𝄞'", ParserContext::new())
+        );
+        assert_eq!(
+            Some(vec![Token::invalid(4)]),
+            p.parse(r"'\s'", ParserContext::new().with_release(8))
+        );
+        assert_eq!(
+            Some(vec![
+                Token::invalid(1),
+                Token::new(TokenKind::Char(QuotedPart::Content), 1)
+            ]),
+            p.parse("'a\n'", ParserContext::new())
+        );
+        assert_eq!(
+            Some(vec![Token::invalid(1), Token::invalid(1)]),
+            p.parse("'\\\n", ParserContext::new())
+        );
+    }
+}
@@ -0,0 +1,166 @@
+use lazy_static::lazy_static;
+
+use crate::hashmap;
+use crate::parser::{
+    javadoc::JavadocParser, pattern::Pattern, CommentKind, DocPart, Parser, ParserContext, Token,
+    TokenKind, Value,
+};
+
+lazy_static! {
+    static ref LINE_COMMENT_REGEX: Pattern = Pattern::new(r"^//[^\r\n]*");
+    static ref BLOCK_COMMENT_REGEX: Pattern = Pattern::new(r"^/\*(?s:.*?)(\*/|$)");
+    pub(crate) static ref TAG_REGEX: Pattern = Pattern::new(r"\b(?:TODO|FIXME|XXX)\b");
+}
+
+/// Tokenizes `text` as a comment of `kind`, splitting out `TODO`, `FIXME` and `XXX` tags.
+pub(crate) fn split_tags(text: &str, kind: TokenKind) -> Vec<Token> {
+    let mut tokens = vec![];
+    let mut last = 0;
+    for tag in TAG_REGEX.find_iter(text) {
+        if tag.start() > last {
+            tokens.push(Token::new(kind, tag.start() - last));
+        }
+        tokens.push(Token::new(
+            TokenKind::Comment(CommentKind::Tag),
+            tag.as_str().len(),
+        ));
+        last = tag.end();
+    }
+    if last < text.len() {
+        tokens.push(Token::new(kind, text.len() - last));
+    }
+    tokens
+}
+
+pub struct CommentParser {
+    javadoc: JavadocParser,
+}
+
+impl CommentParser {
+    pub fn new() -> Self {
+        Self {
+            javadoc: JavadocParser::new(),
+        }
+    }
+}
+
+impl Default for CommentParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Parser for CommentParser {
+    fn parse(&self, code: &str, context: ParserContext) -> Option<Vec<Token>> {
+        if let Some(comment) = LINE_COMMENT_REGEX.find(code) {
+            return Some(split_tags(
+                comment.as_str(),
+                TokenKind::Comment(CommentKind::Line),
+            ));
+        }
+        BLOCK_COMMENT_REGEX.captures(code).map(|captures| {
+            let comment = captures.get(0).unwrap().as_str();
+            let mut tokens = if comment.starts_with("/**") && !comment.starts_with("/**/") {
+                self.javadoc.parse(comment, context).unwrap()
+            } else {
+                split_tags(comment, TokenKind::Comment(CommentKind::Block))
+            };
+            if captures.get(1).unwrap().as_str().is_empty() {
+                // Unterminated, the comment runs until the end of the code.
+                tokens[0].add_metadata(hashmap!(String::from("unterminated"), Value::Int(1)));
+            }
+            tokens
+        })
+    }
+
+    fn patterns(&self) -> Vec<&'static Pattern> {
+        let mut patterns: Vec<&'static Pattern> = vec![&LINE_COMMENT_REGEX, &BLOCK_COMMENT_REGEX];
+        patterns.extend(self.javadoc.patterns());
+        patterns
+    }
+
+    fn expected(&self) -> Vec<TokenKind> {
+        vec![
+            TokenKind::Comment(CommentKind::Line),
+            TokenKind::Comment(CommentKind::Block),
+            TokenKind::Comment(CommentKind::Doc(DocPart::Text)),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{DocPart, Parser, ParserContext, Token};
+    use super::*;
+
+    #[test]
+    fn comment_parser() {
+        let p = CommentParser::new();
+        assert_eq!(
+            Some(vec![Token::new(TokenKind::Comment(CommentKind::Line), 8)]),
+            p.parse("// a = 1\nb", ParserContext::new())
+        );
+        assert_eq!(
+            Some(vec![Token::new(TokenKind::Comment(CommentKind::Block), 12)]),
+            p.parse("/* a\n * b */c", ParserContext::new())
+        );
+        assert_eq!(
+            Some(vec![Token::new(
+                TokenKind::Comment(CommentKind::Doc(DocPart::Text)),
+                8
+            )]),
+            p.parse("/** a */ class", ParserContext::new())
+        );
+        assert_eq!(
+            Some(vec![Token::new(TokenKind::Comment(CommentKind::Block), 4)]),
+            p.parse("/**/ class", ParserContext::new())
+        );
+        assert_eq!(None, p.parse("/ 2", ParserContext::new()));
+    }
+
+    #[test]
+    fn unterminated_comment() {
+        let p = CommentParser::new();
+        assert_eq!(
+            Some(vec![Token::with_meta(
+                TokenKind::Comment(CommentKind::Block),
+                6,
+                hashmap!(String::from("unterminated"), Value::Int(1))
+            )]),
+            p.parse("/* a\nb", ParserContext::new())
+        );
+        assert_eq!(
+            Some(vec![
+                Token::with_meta(
+                    TokenKind::Comment(CommentKind::Doc(DocPart::Text)),
+                    4,
+                    hashmap!(String::from("unterminated"), Value::Int(1))
+                ),
+                Token::new(TokenKind::Comment(CommentKind::Tag), 5),
+            ]),
+            p.parse("/** FIXME", ParserContext::new())
+        );
+    }
+
+    #[test]
+    fn comment_tags() {
+        let p = CommentParser::new();
+        assert_eq!(
+            Some(vec![
+                Token::new(TokenKind::Comment(CommentKind::Line), 3),
+                Token::new(TokenKind::Comment(CommentKind::Tag), 4),
+                Token::new(TokenKind::Comment(CommentKind::Line), 11),
+                Token::new(TokenKind::Comment(CommentKind::Tag), 3),
+            ]),
+            p.parse("// TODO: fix this XXX\n", ParserContext::new())
+        );
+        assert_eq!(
+            Some(vec![
+                Token::new(TokenKind::Comment(CommentKind::Block), 3),
+                Token::new(TokenKind::Comment(CommentKind::Tag), 5),
+                Token::new(TokenKind::Comment(CommentKind::Block), 15),
+            ]),
+            p.parse("/* FIXME TODOS XTODO */", ParserContext::new())
+        );
+    }
+}
@@ -0,0 +1,137 @@
+use lazy_static::lazy_static;
+
+use crate::hashmap;
+use crate::parser::{pattern::Pattern, Parser, ParserContext, Token, TokenKind, Value};
+
+/// The characters `Character.isJavaIdentifierStart` accepts, as the inside of a regex
+/// class: letters, letter numbers, currency symbols and connecting punctuation.
+pub(crate) const IDENTIFIER_START: &str = r"\p{L}\p{Nl}\p{Sc}\p{Pc}";
+
+/// The characters `Character.isJavaIdentifierPart` accepts, as the inside of a regex
+/// class. Digits, combining marks and ignorable control characters are added to the
+/// start characters.
+pub(crate) const IDENTIFIER_PART: &str =
+    r"\p{L}\p{Nl}\p{Sc}\p{Pc}\p{Nd}\p{Mn}\p{Mc}\p{Cf}\x00-\x08\x0E-\x1B\x7F-\x9F";
+
+lazy_static! {
+    static ref IDENTIFIER_REGEX: Pattern =
+        Pattern::new(&format!("^[{}][{}]*", IDENTIFIER_START, IDENTIFIER_PART));
+}
+
+/// The naming convention `name` seems to follow, as the metadata key flagging it:
+/// `CONSTANT_CASE`, `TypeName` or `camelCase`.
+fn style(name: &str) -> Option<&'static str> {
+    let first = name.chars().next()?;
+    let upper = name.chars().any(char::is_uppercase);
+    let lower = name.chars().any(char::is_lowercase);
+    if upper && !lower && name.chars().count() > 1 {
+        Some("constant")
+    } else if first.is_uppercase() {
+        Some("type_name")
+    } else if first.is_lowercase() && !name.contains('_') {
+        Some("camel_case")
+    } else {
+        None
+    }
+}
+
+pub struct IdentifierParser;
+
+impl IdentifierParser {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for IdentifierParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Parser for IdentifierParser {
+    fn parse(&self, code: &str, _: ParserContext) -> Option<Vec<Token>> {
+        IDENTIFIER_REGEX.find(code).map(|m| {
+            let name = m.as_str();
+            let mut token = Token::new(TokenKind::Identifier, name.len());
+            if let Some(style) = style(name) {
+                token.add_metadata(hashmap!(String::from(style), Value::Int(1)));
+            }
+            vec![token]
+        })
+    }
+
+    fn patterns(&self) -> Vec<&'static Pattern> {
+        vec![&IDENTIFIER_REGEX]
+    }
+
+    fn expected(&self) -> Vec<TokenKind> {
+        vec![TokenKind::Identifier]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{Parser, ParserContext, Token};
+    use super::*;
+
+    fn len(code: &str) -> Option<usize> {
+        IdentifierParser::new()
+            .parse(code, ParserContext::new())
+            .map(|t| t[0].len)
+    }
+
+    #[test]
+    fn identifier_parser() {
+        let p = IdentifierParser::new();
+        assert_eq!(
+            Some(vec![Token::new(TokenKind::Identifier, 6)]),
+            p.parse("$my_id = 1;", ParserContext::new())
+        );
+        assert_eq!(
+            Some(vec![Token::new(TokenKind::Identifier, 4)]),
+            p.parse("_a12.b", ParserContext::new())
+        );
+        assert_eq!(None, p.parse("1a", ParserContext::new()));
+    }
+
+    #[test]
+    fn unicode_identifiers() {
+        assert_eq!(Some("счётчик".len()), len("счётчик = 0;"));
+        assert_eq!(Some("αβγ_1".len()), len("αβγ_1.δ"));
+        assert_eq!(Some("€uro".len()), len("€uro"));
+        assert_eq!(Some("ⅻ".len()), len("ⅻ"));
+        assert_eq!(Some("‿x".len()), len("‿x"));
+        // Combining marks, other digits and ignorable characters may only follow.
+        assert_eq!(Some("e\u{301}te\u{200B}".len()), len("e\u{301}te\u{200B}"));
+        assert_eq!(Some("x٣".len()), len("x٣ "));
+        assert_eq!(Some(2), len("a\u{7}"));
+        assert_eq!(None, len("\u{301}e"));
+        assert_eq!(None, len("٣x"));
+        assert_eq!(None, len("\u{200B}x"));
+        assert_eq!(Some(1), len("a·"));
+    }
+
+    #[test]
+    fn identifier_styles() {
+        let p = IdentifierParser::new();
+        let style_of = |code| {
+            let token = p.parse(code, ParserContext::new()).unwrap().remove(0);
+            ["constant", "type_name", "camel_case"]
+                .iter()
+                .copied()
+                .find(|key| token.metadata.get(*key) == Some(&Value::Int(1)))
+        };
+        assert_eq!(Some("constant"), style_of("MAX_VALUE"));
+        assert_eq!(Some("constant"), style_of("ПИ2"));
+        assert_eq!(Some("type_name"), style_of("HashMap"));
+        assert_eq!(Some("type_name"), style_of("T"));
+        assert_eq!(Some("type_name"), style_of("Ωmega"));
+        assert_eq!(Some("camel_case"), style_of("getValue"));
+        assert_eq!(Some("camel_case"), style_of("i"));
+        assert_eq!(Some("camel_case"), style_of("значение"));
+        assert_eq!(None, style_of("snake_case"));
+        assert_eq!(None, style_of("_"));
+        assert_eq!(None, style_of("$1"));
+    }
+}
@@ -0,0 +1,220 @@
+use lazy_static::lazy_static;
+
+use crate::parser::{
+    comment::{split_tags, TAG_REGEX},
+    pattern::Pattern,
+    CommentKind, DocPart, Parser, ParserContext, Token, TokenKind,
+};
+
+lazy_static! {
+    static ref DOC_COMMENT_REGEX: Pattern = Pattern::new(r"^/\*\*(?s:.*?)(?:\*/|$)");
+    static ref BLOCK_TAG_REGEX: Pattern = Pattern::new(r"^@[A-Za-z][\w.-]*");
+    static ref PARAM_REGEX: Pattern = Pattern::new(r"^(\s+)(<[A-Za-z_$][\w$]*>|[A-Za-z_$][\w$]*)");
+    static ref INLINE_TAG_REGEX: Pattern = Pattern::new(r"^\{@([A-Za-z][\w.-]*)");
+    static ref REFERENCE_REGEX: Pattern =
+        Pattern::new(r"^(\s+)([\w$.]*(?:#[\w$]*(?:\([^)]*\))?)?)");
+    static ref HTML_REGEX: Pattern = Pattern::new(r"^(?:<!--(?s:.*?)-->|</?[A-Za-z][^<>]*>)");
+}
+
+/// Length of the body of an inline tag, up to its matching closing brace.
+fn inline_body_len(code: &str) -> usize {
+    let mut depth = 0;
+    for (i, c) in code.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' if depth == 0 => return i,
+            '}' => depth -= 1,
+            _ => {}
+        }
+    }
+    code.len()
+}
+
+/// Accumulates a doc comment's tokens, collecting plain text until something is pushed.
+struct Tokens {
+    tokens: Vec<Token>,
+    text: usize,
+}
+
+impl Tokens {
+    fn push_text(&mut self, code: &str, end: usize) {
+        if self.text < end {
+            self.tokens.extend(split_tags(
+                &code[self.text..end],
+                TokenKind::Comment(CommentKind::Doc(DocPart::Text)),
+            ));
+        }
+        self.text = end;
+    }
+
+    /// Pushes a token of `len` bytes starting at `start`, returning where it ends.
+    fn push(&mut self, code: &str, start: usize, part: DocPart, len: usize) -> usize {
+        self.push_text(code, start);
+        if len > 0 {
+            self.tokens
+                .push(Token::new(TokenKind::Comment(CommentKind::Doc(part)), len));
+        }
+        self.text = start + len;
+        self.text
+    }
+}
+
+/// Tokenizes the inside of a doc comment: block tags, `@param` names, inline tags
+/// with their link targets, and HTML tags.
+pub struct JavadocParser;
+
+impl JavadocParser {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for JavadocParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Parser for JavadocParser {
+    fn parse(&self, code: &str, _: ParserContext) -> Option<Vec<Token>> {
+        let code = DOC_COMMENT_REGEX.find(code)?.as_str();
+        let mut tokens = Tokens {
+            tokens: vec![],
+            text: 0,
+        };
+        // Only whitespace and leading asterisks were seen since the last line start.
+        let mut line_start = true;
+        let mut offset = 3;
+        while let Some(c) = code[offset..].chars().next() {
+            let rest = &code[offset..];
+            if line_start && c == '@' {
+                let tag = BLOCK_TAG_REGEX.find(rest).map_or("@", |m| m.as_str());
+                offset = tokens.push(code, offset, DocPart::Tag, tag.len());
+                if tag == "@param" {
+                    if let Some(param) = PARAM_REGEX.captures(&code[offset..]) {
+                        let start = offset + param.get(1).unwrap().as_str().len();
+                        let name = param.get(2).unwrap().as_str();
+                        offset = tokens.push(code, start, DocPart::Param, name.len());
+                    }
+                }
+                line_start = false;
+            } else if let Some(tag) = INLINE_TAG_REGEX.captures(rest) {
+                let name = tag.get(1).unwrap().as_str();
+                offset = tokens.push(code, offset, DocPart::Inline, tag[0].len());
+                if matches!(name, "link" | "linkplain" | "see" | "value") {
+                    if let Some(reference) = REFERENCE_REGEX.captures(&code[offset..]) {
+                        let start = offset + reference.get(1).unwrap().as_str().len();
+                        let target = reference.get(2).unwrap().as_str();
+                        offset = tokens.push(code, start, DocPart::Link, target.len());
+                    }
+                }
+                let body = inline_body_len(&code[offset..]);
+                let body_part = match name {
+                    "code" | "literal" | "snippet" => DocPart::Code,
+                    _ => DocPart::Text,
+                };
+                offset = tokens.push(code, offset, body_part, body);
+                if code[offset..].starts_with('}') {
+                    offset = tokens.push(code, offset, DocPart::Inline, 1);
+                }
+                line_start = false;
+            } else if let Some(html) = HTML_REGEX.find(rest) {
+                offset = tokens.push(code, offset, DocPart::Html, html.as_str().len());
+                line_start = false;
+            } else {
+                line_start = match c {
+                    '\r' | '\n' => true,
+                    '*' => line_start && !rest.starts_with("*/"),
+                    c => line_start && c.is_whitespace(),
+                };
+                offset += c.len_utf8();
+            }
+        }
+        tokens.push_text(code, code.len());
+        Some(tokens.tokens)
+    }
+
+    fn patterns(&self) -> Vec<&'static Pattern> {
+        vec![
+            &DOC_COMMENT_REGEX,
+            &BLOCK_TAG_REGEX,
+            &PARAM_REGEX,
+            &INLINE_TAG_REGEX,
+            &REFERENCE_REGEX,
+            &HTML_REGEX,
+            &TAG_REGEX,
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{Parser, ParserContext, Token};
+    use super::*;
+
+    #[test]
+    fn javadoc_parser() {
+        let p = JavadocParser::new();
+        let code = "/**\n * Sums <b>two</b> {@code int}s.\n *\n * @param a the {@link Foo#bar(int, T) bar}\n * @return sum @see x\n */";
+        assert_eq!(
+            Some(vec![
+                Token::new(TokenKind::Comment(CommentKind::Doc(DocPart::Text)), 12),
+                Token::new(TokenKind::Comment(CommentKind::Doc(DocPart::Html)), 3),
+                Token::new(TokenKind::Comment(CommentKind::Doc(DocPart::Text)), 3),
+                Token::new(TokenKind::Comment(CommentKind::Doc(DocPart::Html)), 4),
+                Token::new(TokenKind::Comment(CommentKind::Doc(DocPart::Text)), 1),
+                Token::new(TokenKind::Comment(CommentKind::Doc(DocPart::Inline)), 6),
+                Token::new(TokenKind::Comment(CommentKind::Doc(DocPart::Code)), 4),
+                Token::new(TokenKind::Comment(CommentKind::Doc(DocPart::Inline)), 1),
+                Token::new(TokenKind::Comment(CommentKind::Doc(DocPart::Text)), 9),
+                Token::new(TokenKind::Comment(CommentKind::Doc(DocPart::Tag)), 6),
+                Token::new(TokenKind::Comment(CommentKind::Doc(DocPart::Text)), 1),
+                Token::new(TokenKind::Comment(CommentKind::Doc(DocPart::Param)), 1),
+                Token::new(TokenKind::Comment(CommentKind::Doc(DocPart::Text)), 5),
+                Token::new(TokenKind::Comment(CommentKind::Doc(DocPart::Inline)), 6),
+                Token::new(TokenKind::Comment(CommentKind::Doc(DocPart::Text)), 1),
+                Token::new(TokenKind::Comment(CommentKind::Doc(DocPart::Link)), 15),
+                Token::new(TokenKind::Comment(CommentKind::Doc(DocPart::Text)), 4),
+                Token::new(TokenKind::Comment(CommentKind::Doc(DocPart::Inline)), 1),
+                Token::new(TokenKind::Comment(CommentKind::Doc(DocPart::Text)), 4),
+                Token::new(TokenKind::Comment(CommentKind::Doc(DocPart::Tag)), 7),
+                Token::new(TokenKind::Comment(CommentKind::Doc(DocPart::Text)), 15),
+            ]),
+            p.parse(code, ParserContext::new())
+        );
+        assert_eq!(None, p.parse("/* a */", ParserContext::new()));
+    }
+
+    #[test]
+    fn javadoc_inline_tags() {
+        let p = JavadocParser::new();
+        assert_eq!(
+            Some(vec![
+                Token::new(TokenKind::Comment(CommentKind::Doc(DocPart::Text)), 4),
+                Token::new(TokenKind::Comment(CommentKind::Doc(DocPart::Inline)), 9),
+                Token::new(TokenKind::Comment(CommentKind::Doc(DocPart::Code)), 17),
+                Token::new(TokenKind::Comment(CommentKind::Doc(DocPart::Inline)), 1),
+                Token::new(TokenKind::Comment(CommentKind::Doc(DocPart::Text)), 1),
+                Token::new(TokenKind::Comment(CommentKind::Doc(DocPart::Inline)), 12),
+                Token::new(TokenKind::Comment(CommentKind::Doc(DocPart::Inline)), 1),
+                Token::new(TokenKind::Comment(CommentKind::Doc(DocPart::Text)), 3),
+            ]),
+            p.parse(
+                "/** {@snippet :\n if (a) { b; }} {@inheritDoc} */",
+                ParserContext::new()
+            )
+        );
+        assert_eq!(
+            Some(vec![
+                Token::new(TokenKind::Comment(CommentKind::Doc(DocPart::Text)), 4),
+                Token::new(TokenKind::Comment(CommentKind::Doc(DocPart::Tag)), 6),
+                Token::new(TokenKind::Comment(CommentKind::Doc(DocPart::Text)), 1),
+                Token::new(TokenKind::Comment(CommentKind::Doc(DocPart::Param)), 3),
+                Token::new(TokenKind::Comment(CommentKind::Doc(DocPart::Text)), 1),
+                Token::new(TokenKind::Comment(CommentKind::Tag), 4),
+                Token::new(TokenKind::Comment(CommentKind::Doc(DocPart::Text)), 3),
+            ]),
+            p.parse("/** @param <T> TODO */", ParserContext::new())
+        );
+    }
+}
@@ -0,0 +1,422 @@
+use std::collections::HashMap;
+
+use lazy_static::lazy_static;
+
+use crate::parser::{
+    identifier::{IDENTIFIER_PART, IDENTIFIER_START},
+    pattern::Pattern,
+    Feature, KeywordKind, ModifierKind, ModuleKeywordKind, Parser, ParserContext, Token, TokenKind,
+};
+
+/// Keyword categories, in the order they are listed in `parser.rs`.
+const KEYWORDS: &[(KeywordKind, &[&str])] = &[
+    // Standard keywords
+    (KeywordKind::Unused, &["_", "const", "goto"]),
+    (KeywordKind::Package, &["import", "package"]),
+    (KeywordKind::Literal, &["false", "null", "true"]),
+    (
+        KeywordKind::Type,
+        &[
+            "boolean", "byte", "char", "double", "float", "int", "long", "short", "var", "void",
+        ],
+    ),
+    (
+        KeywordKind::Modifier(ModifierKind::Field),
+        &["transient", "volatile"],
+    ),
+    (KeywordKind::Reference, &["super", "this"]),
+    (
+        KeywordKind::Declaration,
+        &["@interface", "class", "enum", "interface", "record"],
+    ),
+    (
+        KeywordKind::Relationship,
+        &["extends", "implements", "permits"],
+    ),
+    (
+        KeywordKind::Modifier(ModifierKind::Access),
+        &["private", "protected", "public"],
+    ),
+    (
+        KeywordKind::Modifier(ModifierKind::Restricting),
+        &["final", "non-sealed", "sealed"],
+    ),
+    (
+        KeywordKind::Modifier(ModifierKind::Method),
+        &["default", "native", "synchronized", "throws"],
+    ),
+    (
+        KeywordKind::Modifier(ModifierKind::Other),
+        &["abstract", "static", "strictfp"],
+    ),
+    (
+        KeywordKind::Control,
+        &[
+            "case", "catch", "do", "else", "finally", "for", "if", "switch", "try", "while",
+        ],
+    ),
+    (
+        KeywordKind::ControlFlow,
+        &["assert", "break", "continue", "return", "throw", "yield"],
+    ),
+    (KeywordKind::Operator, &["instanceof", "new"]),
+];
+
+/// Module keywords, only recognized inside `module-info.java`.
+const MODULE_KEYWORDS: &[(KeywordKind, &[&str])] = &[
+    (KeywordKind::Module(ModuleKeywordKind::Module), &["module"]),
+    (KeywordKind::Module(ModuleKeywordKind::Modifier), &["open"]),
+    (
+        KeywordKind::Module(ModuleKeywordKind::Relationship),
+        &["exports", "opens", "requires", "provides", "uses"],
+    ),
+    (
+        KeywordKind::Module(ModuleKeywordKind::RelationshipModifier),
+        &["static", "transitive"],
+    ),
+    (
+        KeywordKind::Module(ModuleKeywordKind::Other),
+        &["to", "with"],
+    ),
+];
+
+/// Keywords that are identifiers outside of the positions they are reserved in.
+const CONTEXTUAL_KEYWORDS: &[&str] = &["non-sealed", "permits", "record", "sealed", "var", "yield"];
+
+fn keyword_kinds(table: &[(KeywordKind, &[&'static str])]) -> HashMap<&'static str, KeywordKind> {
+    let mut kinds = HashMap::new();
+    for (kind, keywords) in table {
+        for keyword in keywords.iter() {
+            kinds.insert(*keyword, *kind);
+        }
+    }
+    kinds
+}
+
+lazy_static! {
+    static ref KEYWORD_KINDS: HashMap<&'static str, KeywordKind> = keyword_kinds(KEYWORDS);
+    static ref MODULE_KEYWORD_KINDS: HashMap<&'static str, KeywordKind> =
+        keyword_kinds(MODULE_KEYWORDS);
+    static ref WORD_REGEX: Pattern = Pattern::new(&format!(
+        "^(?:@interface|non-sealed|[{}][{}]*)",
+        IDENTIFIER_START, IDENTIFIER_PART
+    ));
+    static ref IDENTIFIER_PART_REGEX: Pattern = Pattern::new(&format!("^[{}]", IDENTIFIER_PART));
+    static ref TRIVIA_REGEX: Pattern =
+        Pattern::new(r"^(?:[ \t\x0C\r\n]|//[^\r\n]*|/\*(?s:.*?)\*/)*");
+    static ref IDENTIFIER_START_REGEX: Pattern = Pattern::new(&format!("^[{}]", IDENTIFIER_START));
+    static ref NOT_YIELD_STATEMENT_REGEX: Pattern =
+        Pattern::new(r"^(?:[.\[;,):]|=[^=]|\+\+|--|(?:[+\-*/%&|^]|<<|>>>?)=)");
+    static ref RECORD_HEADER_REGEX: Pattern = Pattern::new(&format!(
+        r"^[{}][{}]*[ \t\x0C\r\n]*[(<]",
+        IDENTIFIER_START, IDENTIFIER_PART
+    ));
+    static ref CLASS_MODIFIER_REGEX: Pattern = Pattern::new(&format!(
+        r"^(?:@|(?:abstract|class|final|interface|non-sealed|private|protected|public|sealed|static|strictfp)(?:[^{}]|$))",
+        IDENTIFIER_PART
+    ));
+    static ref MODULE_NAME_END_REGEX: Pattern = Pattern::new(r"^(?:[;.,]|$)");
+}
+
+/// Skips whitespace and comments to reach the next token.
+fn next_token(code: &str) -> &str {
+    &code[TRIVIA_REGEX.find(code).map_or(0, |m| m.end())..]
+}
+
+/// Decides whether a keyword that is only reserved in certain positions acts as one here.
+/// `rest` is the code following the keyword.
+fn is_keyword_here(word: &str, rest: &str, context: ParserContext) -> bool {
+    let next = next_token(rest);
+    if context.is_after_dot() {
+        return !CONTEXTUAL_KEYWORDS.contains(&word);
+    }
+    match word {
+        "_" => context.supports(Feature::UnderscoreKeyword),
+        "var" => {
+            context.supports(Feature::LocalVariableTypeInference)
+                && IDENTIFIER_START_REGEX.is_match(next)
+        }
+        "yield" => {
+            context.supports(Feature::SwitchExpressions)
+                && context.is_statement_start()
+                && !NOT_YIELD_STATEMENT_REGEX.is_match(next)
+        }
+        "record" => {
+            context.supports(Feature::Records)
+                && context.is_statement_start()
+                && RECORD_HEADER_REGEX.is_match(next)
+        }
+        "sealed" | "non-sealed" => {
+            context.supports(Feature::SealedClasses)
+                && context.is_statement_start()
+                && CLASS_MODIFIER_REGEX.is_match(next)
+        }
+        "permits" => context.supports(Feature::SealedClasses) && context.is_type_declaration_head(),
+        _ => true,
+    }
+}
+
+pub struct KeywordParser;
+
+impl KeywordParser {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for KeywordParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Parser for KeywordParser {
+    fn parse(&self, code: &str, context: ParserContext) -> Option<Vec<Token>> {
+        let word = WORD_REGEX.find(code)?.as_str();
+        let rest = &code[word.len()..];
+        if IDENTIFIER_PART_REGEX.is_match(rest) {
+            return None;
+        }
+        if context.is_module_info() && context.supports(Feature::Modules) && !context.is_after_dot()
+        {
+            // `requires transitive;` names a module called `transitive`.
+            if let Some(kind) = MODULE_KEYWORD_KINDS.get(word) {
+                if !MODULE_NAME_END_REGEX.is_match(next_token(rest)) {
+                    return Some(vec![Token::new(TokenKind::Keyword(*kind), word.len())]);
+                }
+            }
+        }
+        if word == "_" && context.supports(Feature::UnnamedVariables) {
+            return Some(vec![Token::new(
+                TokenKind::Keyword(KeywordKind::Unnamed),
+                1,
+            )]);
+        }
+        KEYWORD_KINDS
+            .get(word)
+            .filter(|_| is_keyword_here(word, rest, context))
+            .map(|kind| vec![Token::new(TokenKind::Keyword(*kind), word.len())])
+    }
+
+    fn patterns(&self) -> Vec<&'static Pattern> {
+        vec![
+            &WORD_REGEX,
+            &IDENTIFIER_PART_REGEX,
+            &TRIVIA_REGEX,
+            &IDENTIFIER_START_REGEX,
+            &NOT_YIELD_STATEMENT_REGEX,
+            &RECORD_HEADER_REGEX,
+            &CLASS_MODIFIER_REGEX,
+            &MODULE_NAME_END_REGEX,
+        ]
+    }
+
+    fn expected(&self) -> Vec<TokenKind> {
+        let mut kinds = vec![];
+        for &(kind, _) in KEYWORDS.iter().chain(MODULE_KEYWORDS) {
+            if !kinds.contains(&TokenKind::Keyword(kind)) {
+                kinds.push(TokenKind::Keyword(kind));
+            }
+        }
+        kinds
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{lexer::Lexer, Parser, ParserContext, Token};
+    use super::*;
+
+    #[test]
+    fn keyword_parser() {
+        let p = KeywordParser::new();
+        assert_eq!(
+            Some(vec![Token::new(
+                TokenKind::Keyword(KeywordKind::Modifier(ModifierKind::Access)),
+                6
+            )]),
+            p.parse("public class A", ParserContext::new())
+        );
+        assert_eq!(
+            Some(vec![Token::new(
+                TokenKind::Keyword(KeywordKind::ControlFlow),
+                6
+            )]),
+            p.parse("return;", ParserContext::new())
+        );
+        assert_eq!(
+            Some(vec![Token::new(
+                TokenKind::Keyword(KeywordKind::Declaration),
+                10
+            )]),
+            p.parse("@interface A", ParserContext::new())
+        );
+        assert_eq!(
+            Some(vec![Token::new(
+                TokenKind::Keyword(KeywordKind::Modifier(ModifierKind::Restricting)),
+                10
+            )]),
+            p.parse("non-sealed class", ParserContext::new())
+        );
+        assert_eq!(
+            Some(vec![Token::new(
+                TokenKind::Keyword(KeywordKind::Modifier(ModifierKind::Other)),
+                6
+            )]),
+            p.parse("static", ParserContext::new())
+        );
+        assert_eq!(
+            Some(vec![Token::new(TokenKind::Keyword(KeywordKind::Unused), 1)]),
+            p.parse("_ ", ParserContext::new())
+        );
+        assert_eq!(None, p.parse("classic", ParserContext::new()));
+        assert_eq!(None, p.parse("returned", ParserContext::new()));
+        assert_eq!(None, p.parse("intж", ParserContext::new()));
+        assert_eq!(None, p.parse("new\u{301}", ParserContext::new()));
+        assert_eq!(None, p.parse("int2", ParserContext::new()));
+        assert_eq!(None, p.parse("non-sealedx", ParserContext::new()));
+        assert_eq!(None, p.parse("@interfaces", ParserContext::new()));
+        assert_eq!(None, p.parse("__", ParserContext::new()));
+    }
+
+    #[test]
+    fn keyword_categories() {
+        let p = KeywordParser::new();
+        for (kind, keywords) in KEYWORDS {
+            for keyword in keywords.iter() {
+                let code = match *keyword {
+                    "var" => "var x",
+                    "record" => "record R()",
+                    "sealed" | "non-sealed" => continue,
+                    keyword => keyword,
+                };
+                let context = ParserContext {
+                    type_declaration_head: true,
+                    ..ParserContext::new()
+                };
+                assert_eq!(
+                    Some(vec![Token::new(TokenKind::Keyword(*kind), keyword.len())]),
+                    p.parse(code, context)
+                );
+            }
+        }
+        for (kind, keywords) in MODULE_KEYWORDS {
+            for keyword in keywords.iter() {
+                assert_eq!(
+                    Some(vec![Token::new(TokenKind::Keyword(*kind), keyword.len())]),
+                    p.parse(
+                        &format!("{} x", keyword),
+                        ParserContext::new().with_module_info(true)
+                    )
+                );
+            }
+        }
+    }
+
+    fn keywords(code: &str, context: ParserContext) -> Vec<&str> {
+        Lexer::new()
+            .lex(code, context)
+            .iter()
+            .filter(|t| matches!(t.kind, TokenKind::Keyword(_)))
+            .map(|t| &code[t.span.unwrap().start..t.span.unwrap().end])
+            .collect()
+    }
+
+    #[test]
+    fn contextual_keywords() {
+        let c = ParserContext::new();
+        assert_eq!(vec!["var"], keywords("var var = yield;", c));
+        assert_eq!(
+            vec!["var", "record", "int"],
+            keywords("var ж = 1; record Точка(int x) {}", c)
+        );
+        assert_eq!(
+            vec!["int", "switch", "case", "yield", "default", "var", "yield", "default", "yield"],
+            keywords("int x = switch (a) { case 1: yield 2; default -> { var y = 3; yield y; } default: yield -1; };", c)
+        );
+        assert_eq!(
+            Vec::<&str>::new(),
+            keywords("yield = 1; yield.x(); yield++;", c)
+        );
+        assert_eq!(
+            vec!["case", "if", "yield", "else", "yield", "while", "yield"],
+            keywords(
+                "case 1: if (f(a)) yield 2; else yield 3; while (b) yield (c);",
+                c
+            )
+        );
+        assert_eq!(
+            vec!["do", "yield", "while"],
+            keywords("do yield 1; while (yield(x));", c)
+        );
+        assert_eq!(
+            vec!["public", "record", "implements"],
+            keywords("public record Point<T>(T x) implements Shape {}", c)
+        );
+        assert_eq!(Vec::<&str>::new(), keywords("record = record(record);", c));
+        assert_eq!(
+            vec!["public", "sealed", "interface", "permits", "final", "class", "non-sealed", "class"],
+            keywords(
+                "public sealed interface S permits A {} final class A {} @Deprecated non-sealed class B {}",
+                c
+            )
+        );
+        assert_eq!(
+            vec!["int", "int"],
+            keywords("int sealed = 1; int permits = sealed;", c)
+        );
+        assert_eq!(vec!["class"], keywords("f(Foo.class, permits);", c));
+        assert_eq!(Vec::<&str>::new(), keywords("module to with", c));
+    }
+
+    #[test]
+    fn release_gated_keywords() {
+        let code = "sealed interface S permits R {} record R(int x) {} var _ = switch (x) { default: yield 1; };";
+        let java8 = ParserContext::new().with_release(8);
+        assert_eq!(
+            vec!["interface", "int", "switch", "default"],
+            keywords(code, java8)
+        );
+        assert_eq!(
+            vec!["interface", "int", "var", "_", "switch", "default", "yield"],
+            keywords(code, java8.with_release(14))
+        );
+        assert_eq!(
+            vec![
+                "interface",
+                "record",
+                "int",
+                "var",
+                "_",
+                "switch",
+                "default",
+                "yield"
+            ],
+            keywords(code, java8.with_release(14).with_preview(true))
+        );
+        let tokens = Lexer::new().lex(code, ParserContext::new());
+        assert_eq!(
+            vec!["keyword.modifier.restricting", "keyword.unused"],
+            vec![tokens[0].name(), tokens[27].name()]
+        );
+        let tokens = Lexer::new().lex(code, ParserContext::new().with_preview(true));
+        assert_eq!(TokenKind::Keyword(KeywordKind::Unnamed), tokens[27].kind);
+        assert_eq!(
+            Vec::<&str>::new(),
+            keywords("module m { requires a; }", java8.with_module_info(true))
+        );
+    }
+
+    #[test]
+    fn module_keywords() {
+        let c = ParserContext::new().with_module_info(true);
+        assert_eq!(
+            vec!["open", "module", "requires", "transitive", "requires", "static", "exports", "to", "provides", "with"],
+            keywords(
+                "open module a.b { requires transitive c; requires static d; exports e.to to f; provides g with h; }",
+                c
+            )
+        );
+        assert_eq!(vec!["requires"], keywords("requires transitive;", c));
+    }
+}
@@ -0,0 +1,1281 @@
+use std::{
+    borrow::Cow,
+    fmt::{self, Display, Formatter},
+    ops::Range,
+};
+
+use crate::parser::{
+    annotation::AnnotationParser, character::CharacterParser, comment::CommentParser,
+    identifier::IdentifierParser, keyword::KeywordParser, number::NumberParser,
+    operator::OperatorParser, string::StringParser, text_block::TextBlockParser,
+    unicode::Translation, whitespace::WhitespaceParser, CommentKind, Diagnostic, DiagnosticKind,
+    Fix, ParseError, Parser, ParserContext, Position, QuotedPart, SeparatorKind, Span, Token,
+    TokenKind,
+};
+
+/// Tracks the byte offset and line/column position while walking over the source.
+struct Cursor {
+    offset: usize,
+    position: Position,
+    after_cr: bool,
+}
+
+impl Cursor {
+    /// A cursor at byte `offset` of `code`, which is at `position`.
+    fn new(code: &str, offset: usize, position: Position) -> Self {
+        Self {
+            offset,
+            position,
+            after_cr: code[..offset].ends_with('\r'),
+        }
+    }
+
+    fn advance(&mut self, text: &str) {
+        for c in text.chars() {
+            match c {
+                // The `\n` of a `\r\n` pair, the line was already advanced on `\r`.
+                '\n' if self.after_cr => {}
+                '\r' | '\n' => {
+                    self.position.line += 1;
+                    self.position.column = 0;
+                    self.position.utf16_column = 0;
+                }
+                _ => {
+                    self.position.column += c.len_utf8();
+                    self.position.utf16_column += c.len_utf16();
+                }
+            }
+            self.after_cr = c == '\r';
+        }
+        self.offset += text.len();
+    }
+
+    fn locate(&mut self, code: &str, token: &mut Token) {
+        let (start, start_pos) = (self.offset, self.position);
+        self.advance(&code[start..start + token.len]);
+        token.span = Some(Span {
+            start,
+            end: self.offset,
+            start_pos,
+            end_pos: self.position,
+        });
+    }
+}
+
+/// Fills in the spans of the diagnostics carried by `tokens`, which must already be located.
+fn locate_diagnostics(tokens: &mut [Token]) {
+    for i in 0..tokens.len() {
+        let start = tokens[i].span.unwrap();
+        let end = match &tokens[i].diagnostic {
+            Some(diagnostic) => start.start + diagnostic.len,
+            None => continue,
+        };
+        let end_pos = tokens[i..]
+            .iter()
+            .map(|t| t.span.unwrap())
+            .find(|s| s.end >= end)
+            .map_or(start.end_pos, |s| s.end_pos);
+        if let Some(diagnostic) = &mut tokens[i].diagnostic {
+            diagnostic.span = Some(Span {
+                start: start.start,
+                end,
+                start_pos: start.start_pos,
+                end_pos,
+            });
+        }
+    }
+}
+
+/// Tokenizes a whole source buffer by repeatedly dispatching to the sub-parsers.
+///
+/// The returned tokens always cover the entire input, so one bad character never
+/// affects the rest of the file: anything no sub-parser recognizes is emitted as an
+/// `unknown` token with an illegal character diagnostic, ending right where some
+/// sub-parser recognizes a token again. Every token
+/// gets its `span` filled in, and the context passed to the sub-parsers is
+/// advanced past each token so contextual keywords can be told apart.
+///
+/// Unicode escapes are translated before the sub-parsers see the code, but token
+/// lengths and spans always refer to the raw code, escapes included.
+pub struct Lexer {
+    parsers: Vec<Box<dyn Parser>>,
+}
+
+impl Lexer {
+    pub fn new() -> Self {
+        Self {
+            parsers: vec![
+                Box::new(WhitespaceParser::new()),
+                Box::new(CommentParser::new()),
+                Box::new(TextBlockParser::new()),
+                Box::new(StringParser::new()),
+                Box::new(CharacterParser::new()),
+                Box::new(NumberParser::new()),
+                Box::new(AnnotationParser::new()),
+                Box::new(KeywordParser::new()),
+                Box::new(IdentifierParser::new()),
+                Box::new(OperatorParser::new()),
+            ],
+        }
+    }
+
+    /// The tokens of the first sub-parser recognizing the code `code` starts with.
+    fn recognize(&self, code: &str, context: ParserContext) -> Option<Vec<Token>> {
+        self.parsers
+            .iter()
+            .filter_map(|p| p.parse(code, context))
+            .find(|t| t.iter().map(|t| t.len).sum::<usize>() > 0)
+    }
+
+    /// Covers the unrecognized code `code` starts with, up to the next character
+    /// a sub-parser recognizes that does not continue the same word, so that `0x1g`
+    /// is one chunk rather than `0` followed by the identifier `x1g`.
+    fn unrecognized(&self, code: &str, context: ParserContext) -> Token {
+        let is_word = |c: char| c.is_alphanumeric() || c == '_' || c == '$' || !c.is_ascii();
+        let len = code
+            .char_indices()
+            .skip(1)
+            .find(|&(i, c)| {
+                let prev = code[..i].chars().next_back().unwrap();
+                !(is_word(prev) && is_word(c)) && self.recognize(&code[i..], context).is_some()
+            })
+            .map_or(code.len(), |(i, _)| i);
+        // The character that fails is the one after the longest start of the chunk a
+        // sub-parser takes whole.
+        let word = &code[..len];
+        let start = word
+            .char_indices()
+            .map(|(i, _)| i)
+            .rev()
+            .filter(|&i| i > 0)
+            .find(|&i| {
+                self.recognize(&word[..i], context)
+                    .is_some_and(|t| t.iter().map(|t| t.len).sum::<usize>() == i)
+            })
+            .unwrap_or(0);
+        let c = word[start..].chars().next().unwrap();
+        let kind = DiagnosticKind::IllegalCharacter;
+        // Like javac, only characters that would not print are escaped.
+        let message = match c {
+            ' '..='~' => format!("{}: '{}'", kind.message(), c),
+            _ => format!("{}: '\\u{:04x}'", kind.message(), c as u32),
+        };
+        let fix = Fix::new("Remove the illegal characters", 0..len, String::new());
+        Token::unknown(len).with_diagnostic(Diagnostic::new(kind, message, len).with_fix(fix))
+    }
+
+    pub fn lex(&self, code: &str, context: ParserContext) -> Vec<Token> {
+        self.lex_from(
+            code,
+            0,
+            Position::default(),
+            context,
+            code.len(),
+            |_, _, _| false,
+        )
+    }
+
+    /// Lexes `code` like `lex`, keeping what `relex` needs to update the tokens after
+    /// edits.
+    pub fn lex_incremental(&self, code: &str, context: ParserContext) -> Lexed {
+        let mut restarts = vec![];
+        let mut count = 0;
+        let tokens = self.lex_from(
+            code,
+            0,
+            Position::default(),
+            context,
+            code.len(),
+            |batch, after, _| {
+                count += batch.len();
+                if is_restart(batch) {
+                    restarts.push((count, *after));
+                }
+                false
+            },
+        );
+        Lexed {
+            code: String::from(code),
+            tokens,
+            context,
+            restarts,
+        }
+    }
+
+    /// Lexes `code` from byte `from`, which lexing reached at `position` with `context`.
+    /// Lexing ends early when `stop` returns true after the tokens of one sub-parser,
+    /// given those tokens, the context after them and the byte they end at.
+    ///
+    /// Only the code up to the end of the line `until` is in is translated and lexed
+    /// at first. As no lookahead goes past a `;`, `{` or `}`, the tokens up to the
+    /// last of them lex the same as in the whole code. Lexing goes on from there with
+    /// twice as much code, until `stop` returns true or the end of the code.
+    fn lex_from(
+        &self,
+        code: &str,
+        from: usize,
+        position: Position,
+        context: ParserContext,
+        until: usize,
+        mut stop: impl FnMut(&[Token], &ParserContext, usize) -> bool,
+    ) -> Vec<Token> {
+        let mut tokens = vec![];
+        let (mut from, mut position, mut context) = (from, position, context);
+        let mut until = until;
+        loop {
+            let end = code[until..]
+                .find('\n')
+                .map_or(code.len(), |i| until + i + 1);
+            let (window, batches) = self.lex_window(&code[..end], from, position, context);
+            let batch_at = |i: usize| {
+                let first = i.checked_sub(1).map_or(0, |i| batches[i].0);
+                &window[first..batches[i].0]
+            };
+            let keep = match end == code.len() {
+                true => batches.len(),
+                false => (0..batches.len())
+                    .rev()
+                    .find(|&i| is_restart(batch_at(i)))
+                    .map_or(0, |i| i + 1),
+            };
+            let mut first = 0;
+            for &(last, ref after) in &batches[..keep] {
+                let batch = &window[first..last];
+                if stop(batch, after, span(&batch[batch.len() - 1]).end) {
+                    tokens.extend(window.into_iter().take(last));
+                    return tokens;
+                }
+                first = last;
+            }
+            if end == code.len() {
+                tokens.extend(window);
+                return tokens;
+            }
+            if keep > 0 {
+                let last = span(&window[first - 1]);
+                (from, position, context) = (last.end, last.end_pos, batches[keep - 1].1);
+            }
+            until = code.len().min(end + (end - from).max(1));
+            tokens.extend(window.into_iter().take(first));
+        }
+    }
+
+    /// Lexes `code` from byte `from`, which lexing reached at `position` with `context`,
+    /// to its end. Along with the tokens, gives where the tokens of each sub-parser end
+    /// and the context after them.
+    fn lex_window(
+        &self,
+        code: &str,
+        from: usize,
+        position: Position,
+        context: ParserContext,
+    ) -> (Vec<Token>, Vec<(usize, ParserContext)>) {
+        let translation = Translation::new(&code[from..]);
+        let text = translation.text();
+        let mut tokens = vec![];
+        let mut batches = vec![];
+        let mut context = context;
+        let mut cursor = Cursor::new(code, from, position);
+        let mut offset = 0;
+        while offset < text.len() {
+            let rest = &text[offset..];
+            let parsed = self
+                .recognize(rest, context)
+                .unwrap_or_else(|| vec![self.unrecognized(rest, context)]);
+            let first = tokens.len();
+            for mut token in parsed {
+                let (start, raw_start) = (offset, cursor.offset);
+                offset += token.len;
+                context.advance(&token, &text[start..offset]);
+                // The parsers see the translated text, lengths are measured in the raw one.
+                if translation.is_translated() {
+                    token.len = from + translation.raw_offset(offset) - raw_start;
+                    let raw = |offset| from + translation.raw_offset(start + offset) - raw_start;
+                    if let Some(diagnostic) = &mut token.diagnostic {
+                        diagnostic.len = raw(diagnostic.len);
+                        if let Some(fix) = &mut diagnostic.fix {
+                            fix.span = raw(fix.span.start)..raw(fix.span.end);
+                        }
+                    }
+                }
+                cursor.locate(code, &mut token);
+                tokens.push(token);
+            }
+            locate_diagnostics(&mut tokens[first..]);
+            batches.push((tokens.len(), context));
+        }
+        (tokens, batches)
+    }
+
+    /// Applies `edit` to the code of `lexed`, and updates its tokens to match.
+    ///
+    /// Only the tokens from the last `;`, `{` or `}` before the edit are lexed again,
+    /// as no lookahead goes past them, until they line up with the previous tokens
+    /// again: the same tokens ending at the same place in the shifted code, with the
+    /// same context after them. Edits opening or closing a block comment or text block
+    /// keep lexing until that is the case, or to the end of the code. The spans of
+    /// the previous tokens after that are shifted.
+    ///
+    /// Edits whose range is not within the code, or splits a character, are rejected
+    /// and leave `lexed` as it was.
+    pub fn relex(&self, lexed: &mut Lexed, edit: &Edit) -> Result<Relexed, EditError> {
+        let Lexed {
+            code,
+            tokens,
+            context,
+            restarts,
+        } = lexed;
+        let Edit { range, text } = edit;
+        if range.start > range.end || range.end > code.len() {
+            return Err(EditError::OutOfBounds(range.clone()));
+        } else if !code.is_char_boundary(range.start) || !code.is_char_boundary(range.end) {
+            return Err(EditError::NotCharBoundary(range.clone()));
+        }
+        let restart_index =
+            restarts.partition_point(|&(i, _)| span(&tokens[i - 1]).end <= range.start);
+        let (restart, context) = match restart_index {
+            0 => (0, *context),
+            _ => restarts[restart_index - 1],
+        };
+        // The context after the previous tokens the edit touches, which can only be
+        // replayed before the edit.
+        let mut old = restart;
+        let mut old_context = context;
+        while old < tokens.len() && span(&tokens[old]).start < range.end {
+            advance(
+                &mut old_context,
+                &tokens[old],
+                &code[span(&tokens[old]).range()],
+            );
+            old += 1;
+        }
+        code.replace_range(range.clone(), text);
+        let edit_end = range.start + text.len();
+        let shifted = |offset: usize| offset - range.end + edit_end;
+        let (from, position) = match restart {
+            0 => (0, Position::default()),
+            _ => {
+                let span = span(&tokens[restart - 1]);
+                (span.end, span.end_pos)
+            }
+        };
+        let mut converged = None;
+        let mut new_restarts = vec![];
+        let mut count = restart;
+        let relexed = self.lex_from(
+            code,
+            from,
+            position,
+            context,
+            edit_end,
+            |batch, context, end| {
+                count += batch.len();
+                if is_restart(batch) {
+                    new_restarts.push((count, *context));
+                }
+                if end < edit_end {
+                    return false;
+                }
+                while old < tokens.len() && shifted(span(&tokens[old]).start) < end {
+                    let span = span(&tokens[old]);
+                    advance(
+                        &mut old_context,
+                        &tokens[old],
+                        &code[shifted(span.start)..shifted(span.end)],
+                    );
+                    old += 1;
+                }
+                let lined_up = old < tokens.len()
+                    && shifted(span(&tokens[old]).start) == end
+                    && *context == old_context
+                    && old >= batch.len()
+                    && batch
+                        .iter()
+                        .zip(&tokens[old - batch.len()..old])
+                        .all(|(new, old)| same(new, old));
+                if lined_up {
+                    converged = Some(old);
+                }
+                lined_up
+            },
+        );
+        let end = converged.unwrap_or(tokens.len());
+        if converged.is_some() {
+            let (old_start, new_end) = (span(&tokens[end]), span(relexed.last().unwrap()));
+            for token in &mut tokens[end..] {
+                shift(token.span.as_mut().unwrap(), old_start, new_end);
+                if let Some(diagnostic) = &mut token.diagnostic {
+                    shift(diagnostic.span.as_mut().unwrap(), old_start, new_end);
+                }
+            }
+        }
+        let len = relexed.len();
+        tokens.splice(restart..end, relexed);
+        // The restarts up to `restart` stay, the relexed ones replace those up to
+        // `end`, and the ones after move along with their tokens.
+        let after = restarts.partition_point(|&(i, _)| i <= end);
+        let moved: Vec<_> = restarts[after..]
+            .iter()
+            .map(|&(i, context)| (i - end + restart + len, context))
+            .collect();
+        restarts.truncate(restart_index);
+        restarts.extend(new_restarts);
+        restarts.extend(moved);
+        Ok(Relexed {
+            tokens: restart..restart + len,
+            replaced: end - restart,
+        })
+    }
+
+    /// Lexes one line of code, its line terminator included if it has one, starting in
+    /// `state`, the state the previous line ended in. Spans are relative to the line.
+    ///
+    /// Block comments and text blocks spanning several lines give a token on each of
+    /// their lines, and a line ending inside of one is not reported as unterminated.
+    /// Lookahead stops at the end of the line though, so type arguments only get
+    /// `separator.angle` tokens when they close on the line they open on, and Javadoc
+    /// inline tags end with their line. The `indent` of a text block depends on its
+    /// later lines, so its opening delimiter has none when the text block continues.
+    pub fn lex_line(&self, state: &LineState, line: &str) -> (Vec<Token>, LineState) {
+        let prefix = state.continuation.prefix();
+        let code = format!("{}{}", prefix, line);
+        let mut context = state.context;
+        let mut continuation = Continuation::Code;
+        let mut tokens = self.lex_from(
+            &code,
+            0,
+            Position::default(),
+            context,
+            code.len(),
+            |batch, after, _| {
+                context = *after;
+                continuation = Continuation::left_open(batch);
+                false
+            },
+        );
+        match continuation {
+            Continuation::Code => {}
+            Continuation::TextBlock => {
+                let i = tokens
+                    .iter()
+                    .rposition(|t| t.metadata.contains_key("indent"))
+                    .unwrap();
+                // Only an opening delimiter without a line terminator is invalid then.
+                if tokens.get(i + 1).is_some_and(|t| {
+                    matches!(t.kind, TokenKind::Whitespace | TokenKind::LineTerminator)
+                }) {
+                    tokens[i].kind = TokenKind::String(QuotedPart::Quote);
+                }
+                tokens[i].metadata.remove("indent");
+            }
+            _ => {
+                let token = tokens
+                    .iter_mut()
+                    .rev()
+                    .find(|t| t.metadata.contains_key("unterminated"))
+                    .unwrap();
+                token.metadata.remove("unterminated");
+            }
+        }
+        // Drop what the prefix added, and locate the rest in the line itself.
+        let mut skip = prefix.len();
+        tokens.retain_mut(|token| {
+            let len = skip.min(token.len);
+            token.len -= len;
+            if let Some(diagnostic) = &mut token.diagnostic {
+                diagnostic.len = diagnostic.len.saturating_sub(len);
+            }
+            skip -= len;
+            token.len > 0
+        });
+        let mut cursor = Cursor::new(line, 0, Position::default());
+        for token in &mut tokens {
+            cursor.locate(line, token);
+        }
+        locate_diagnostics(&mut tokens);
+        (
+            tokens,
+            LineState {
+                context,
+                continuation,
+            },
+        )
+    }
+}
+
+/// What a line starts inside of.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Continuation {
+    Code,
+    BlockComment,
+    DocComment,
+    TextBlock,
+}
+
+impl Continuation {
+    /// What the tokens of one sub-parser leave open when they are the last of a line.
+    fn left_open(batch: &[Token]) -> Self {
+        let first = &batch[0];
+        let last = &batch[batch.len() - 1];
+        if first.metadata.contains_key("unterminated") {
+            match first.kind {
+                TokenKind::Comment(CommentKind::Doc(_)) => Continuation::DocComment,
+                _ => Continuation::BlockComment,
+            }
+        } else if first.metadata.contains_key("indent")
+            && (batch.len() == 1 || last.kind != TokenKind::String(QuotedPart::Quote))
+        {
+            Continuation::TextBlock
+        } else {
+            Continuation::Code
+        }
+    }
+
+    /// The code to lex before a line for it to start inside of this.
+    fn prefix(self) -> &'static str {
+        match self {
+            Continuation::Code => "",
+            // The spaces keep a line starting with `/` from closing the comment early.
+            Continuation::BlockComment => "/* ",
+            Continuation::DocComment => "/** ",
+            Continuation::TextBlock => "\"\"\"\n",
+        }
+    }
+}
+
+/// The state lexing carries from one line to the next, for engines that highlight
+/// code line by line: the context, generic depth included, and whether the line
+/// starts inside a block comment or text block. Lines starting in equal states lex
+/// the same, so only lines whose state changed need to be lexed again.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LineState {
+    context: ParserContext,
+    continuation: Continuation,
+}
+
+impl LineState {
+    /// The state of the first line of code lexed with `context`.
+    pub fn new(context: ParserContext) -> Self {
+        Self {
+            context,
+            continuation: Continuation::Code,
+        }
+    }
+}
+
+impl Default for LineState {
+    fn default() -> Self {
+        Self::new(ParserContext::new())
+    }
+}
+
+/// A change to the code: the bytes in `range` replaced with `text`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Edit {
+    pub range: Range<usize>,
+    pub text: String,
+}
+
+impl Edit {
+    pub fn new(range: Range<usize>, text: &str) -> Self {
+        Self {
+            range,
+            text: String::from(text),
+        }
+    }
+}
+
+/// Why `Lexer::relex` could not apply an edit.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EditError {
+    /// The range is reversed or goes past the end of the code.
+    OutOfBounds(Range<usize>),
+    /// One end of the range is inside a character.
+    NotCharBoundary(Range<usize>),
+}
+
+impl Display for EditError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            EditError::OutOfBounds(range) => write!(f, "edit range {:?} is out of bounds", range),
+            EditError::NotCharBoundary(range) => {
+                write!(f, "edit range {:?} splits a character", range)
+            }
+        }
+    }
+}
+
+impl std::error::Error for EditError {}
+
+/// The tokens `Lexer::relex` changed: the ones in `tokens` replaced `replaced`
+/// previous tokens starting at the same index.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Relexed {
+    pub tokens: Range<usize>,
+    pub replaced: usize,
+}
+
+/// Code lexed by `Lexer::lex_incremental`, along with its tokens, which `Lexer::relex`
+/// keeps up to date with edits.
+#[derive(Debug)]
+pub struct Lexed {
+    code: String,
+    tokens: Vec<Token>,
+    /// The context the code was lexed with.
+    context: ParserContext,
+    /// The index of each token right after a `;`, `{` or `}`, where lexing can restart,
+    /// and the context lexing reached there.
+    restarts: Vec<(usize, ParserContext)>,
+}
+
+impl Lexed {
+    pub fn code(&self) -> &str {
+        &self.code
+    }
+
+    pub fn tokens(&self) -> &[Token] {
+        &self.tokens
+    }
+}
+
+/// Whether the tokens of one sub-parser are a `;`, `{` or `}`, which no lookahead
+/// goes past, so that lexing can restart after them.
+fn is_restart(batch: &[Token]) -> bool {
+    matches!(
+        batch,
+        [Token {
+            kind: TokenKind::Separator(SeparatorKind::Semicolon | SeparatorKind::Brace),
+            ..
+        }]
+    )
+}
+
+fn span(token: &Token) -> Span {
+    token.span.unwrap()
+}
+
+/// Advances `context` past `token`, whose raw source text is `raw`, the way lexing does.
+fn advance(context: &mut ParserContext, token: &Token, raw: &str) {
+    let text = if raw.contains('\\') {
+        Cow::Owned(Translation::new(raw).text().to_string())
+    } else {
+        Cow::Borrowed(raw)
+    };
+    context.advance(token, &text);
+}
+
+/// Whether two tokens were lexed the same, wherever they are in the code.
+fn same(a: &Token, b: &Token) -> bool {
+    let diagnostic = |t: &Token| t.diagnostic.as_ref().map(|d| (d.kind, d.len));
+    a.kind == b.kind && a.len == b.len && a.metadata == b.metadata && diagnostic(a) == diagnostic(b)
+}
+
+/// Moves `span`, which starts at or after `from`, as if `from` had moved to `to`.
+fn shift(span: &mut Span, from: Span, to: Span) {
+    let (from_pos, to_pos) = (from.start_pos, to.end_pos);
+    let position = |p: Position| {
+        let line = p.line - from_pos.line + to_pos.line;
+        // Only the rest of the line the edit ends on moves sideways.
+        if p.line == from_pos.line {
+            Position {
+                line,
+                column: p.column - from_pos.column + to_pos.column,
+                utf16_column: p.utf16_column - from_pos.utf16_column + to_pos.utf16_column,
+            }
+        } else {
+            Position { line, ..p }
+        }
+    };
+    *span = Span {
+        start: span.start - from.start + to.end,
+        end: span.end - from.start + to.end,
+        start_pos: position(span.start_pos),
+        end_pos: position(span.end_pos),
+    };
+}
+
+/// The problems diagnosed in lexed `tokens`, with spans into the lexed code.
+pub fn errors(tokens: &[Token]) -> Vec<ParseError> {
+    tokens
+        .iter()
+        .filter_map(|t| {
+            Some(ParseError::from_diagnostic(
+                t.diagnostic.as_ref()?,
+                t.span?.start,
+            ))
+        })
+        .collect()
+}
+
+impl Default for Lexer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Parser for Lexer {
+    fn parse(&self, code: &str, context: ParserContext) -> Option<Vec<Token>> {
+        Some(self.lex(code, context))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{
+        Diagnostic, DiagnosticKind, ErrorCode, NumberPart, ParserContext, Position, Span, Token,
+        TokenKind,
+    };
+    use super::*;
+
+    fn names(tokens: &[Token]) -> Vec<(&str, usize)> {
+        tokens.iter().map(|t| (t.name(), t.len)).collect()
+    }
+
+    #[test]
+    fn lexer() {
+        let l = Lexer::new();
+        let code = "var x = 0x1F; // hex\nString s = \"a\";";
+        let tokens = l.lex(code, ParserContext::new());
+        assert_eq!(
+            vec![
+                ("keyword.type", 3),
+                ("whitespace", 1),
+                ("identifier", 1),
+                ("whitespace", 1),
+                ("operator.assignment", 1),
+                ("whitespace", 1),
+                ("number.prefix", 2),
+                ("number", 2),
+                ("separator.semicolon", 1),
+                ("whitespace", 1),
+                ("comment.line", 6),
+                ("line_terminator", 1),
+                ("identifier", 6),
+                ("whitespace", 1),
+                ("identifier", 1),
+                ("whitespace", 1),
+                ("operator.assignment", 1),
+                ("whitespace", 1),
+                ("string.quote", 1),
+                ("string", 1),
+                ("string.quote", 1),
+                ("separator.semicolon", 1),
+            ],
+            names(&tokens)
+        );
+        assert_eq!(code.len(), tokens.iter().map(|t| t.len).sum::<usize>());
+    }
+
+    #[test]
+    fn token_kind_names() {
+        let l = Lexer::new();
+        let code = "/** @param a {@link B} <p> */ @interface A { public int x = -1_0.5e+3f; char c = '\\n'; } // TODO\n";
+        for token in l.lex(code, ParserContext::new()) {
+            assert_eq!(Some(token.kind), TokenKind::from_name(token.name()));
+        }
+        assert_eq!(None, TokenKind::from_name("number.exponent"));
+        assert_eq!(
+            "number.e.sign",
+            TokenKind::Number(NumberPart::ExponentSign).to_string()
+        );
+    }
+
+    #[test]
+    fn token_kind_names_round_trip() {
+        use super::super::{
+            AnnotationPart as A, DocPart as D, KeywordKind as K, ModifierKind as M,
+            ModuleKeywordKind as MK, NumberPart as N, OperatorKind as O, QuotedPart as Q,
+            SeparatorKind as S, TokenKind as T,
+        };
+        let operators = [
+            O::Arithmetic,
+            O::Bitwise,
+            O::Shift,
+            O::Comparison,
+            O::Logical,
+            O::Assignment,
+            O::Conditional,
+            O::Arrow,
+        ];
+        let separators = [
+            S::Paren,
+            S::Brace,
+            S::Bracket,
+            S::Angle,
+            S::Semicolon,
+            S::Comma,
+            S::Dot,
+            S::Ellipsis,
+            S::At,
+            S::MethodReference,
+        ];
+        let modifiers = [M::Field, M::Access, M::Restricting, M::Method, M::Other];
+        let modules = [
+            MK::Module,
+            MK::Modifier,
+            MK::Relationship,
+            MK::RelationshipModifier,
+            MK::Other,
+        ];
+        let mut keywords = vec![
+            K::Unused,
+            K::Package,
+            K::Literal,
+            K::Type,
+            K::Reference,
+            K::Declaration,
+            K::Relationship,
+            K::Control,
+            K::ControlFlow,
+            K::Operator,
+            K::Unnamed,
+        ];
+        keywords.extend(modifiers.iter().copied().map(K::Modifier));
+        keywords.extend(modules.iter().copied().map(K::Module));
+        let numbers = [
+            N::Sign,
+            N::Prefix,
+            N::Digits,
+            N::Dot,
+            N::Exponent,
+            N::ExponentSign,
+            N::ExponentPower,
+            N::Suffix,
+        ];
+        let quoted = [Q::Quote, Q::Content, Q::Escape];
+        let docs = [
+            D::Text,
+            D::Tag,
+            D::Param,
+            D::Inline,
+            D::Link,
+            D::Code,
+            D::Html,
+        ];
+        let mut comments = vec![CommentKind::Line, CommentKind::Block, CommentKind::Tag];
+        comments.extend(docs.iter().copied().map(CommentKind::Doc));
+        let mut kinds = vec![T::Whitespace, T::LineTerminator, T::Identifier];
+        kinds.extend(operators.iter().copied().map(T::Operator));
+        kinds.extend(separators.iter().copied().map(T::Separator));
+        kinds.extend(keywords.iter().copied().map(T::Keyword));
+        kinds.extend([A::At, A::Name, A::Dot].iter().copied().map(T::Annotation));
+        kinds.extend(numbers.iter().copied().map(T::Number));
+        kinds.extend(quoted.iter().copied().map(T::String));
+        kinds.extend(quoted.iter().copied().map(T::Char));
+        kinds.extend(comments.iter().copied().map(T::Comment));
+        kinds.extend(vec![T::Invalid, T::Unknown]);
+        for kind in &kinds {
+            assert_eq!(Some(*kind), TokenKind::from_name(kind.name()), "{kind:?}");
+        }
+        let mut names: Vec<&str> = kinds.iter().map(|k| k.name()).collect();
+        names.sort();
+        names.dedup();
+        assert_eq!(kinds.len(), names.len());
+    }
+
+    #[test]
+    fn lexer_unknown() {
+        let l = Lexer::new();
+        let tokens = l.lex("a # §", ParserContext::new());
+        assert_eq!(
+            vec![
+                ("identifier", 1),
+                ("whitespace", 1),
+                ("unknown", 1),
+                ("whitespace", 1),
+                ("unknown", 2),
+            ],
+            names(&tokens)
+        );
+        let messages: Vec<&str> = tokens
+            .iter()
+            .filter_map(|t| t.diagnostic.as_ref())
+            .map(|d| d.message.as_str())
+            .collect();
+        assert_eq!(
+            vec!["illegal character: '#'", "illegal character: '\\u00a7'"],
+            messages
+        );
+        assert_eq!(Vec::<Token>::new(), l.lex("", ParserContext::new()));
+        // Malformed words are not split, and the character that breaks them is named.
+        let tokens = l.lex("x = 0x1g + 12§4;", ParserContext::new());
+        assert_eq!(
+            vec![("unknown", 4), ("unknown", 3)],
+            names(&tokens)
+                .into_iter()
+                .filter(|(name, _)| *name == "unknown")
+                .collect::<Vec<_>>()
+        );
+        let messages: Vec<&str> = tokens
+            .iter()
+            .filter_map(|t| t.diagnostic.as_ref())
+            .map(|d| d.message.as_str())
+            .collect();
+        assert_eq!(
+            vec!["illegal character: 'g'", "illegal character: '\\u00a7'"],
+            messages
+        );
+    }
+
+    #[test]
+    fn lexer_recovery() {
+        let l = Lexer::new();
+        let tokens = l.lex(r"int #§\u00a7# x = 1;", ParserContext::new());
+        assert_eq!(
+            vec![
+                ("keyword.type", 3),
+                ("whitespace", 1),
+                ("unknown", 10),
+                ("whitespace", 1),
+                ("identifier", 1),
+            ],
+            names(&tokens[..5])
+        );
+        let diagnostic = tokens[2].diagnostic.as_ref().unwrap();
+        assert_eq!(DiagnosticKind::IllegalCharacter, diagnostic.kind);
+        assert_eq!("illegal character: '#'", diagnostic.message);
+        assert_eq!((4, 14), {
+            let span = diagnostic.span.unwrap();
+            (span.start, span.end)
+        });
+        let errors = errors(&tokens);
+        assert_eq!(1, errors.len());
+        assert_eq!(
+            ErrorCode::Malformed(DiagnosticKind::IllegalCharacter),
+            errors[0].code
+        );
+        assert_eq!(4..14, errors[0].span);
+        assert_eq!(
+            Some(&Fix::new(
+                "Remove the illegal characters",
+                4..14,
+                String::new()
+            )),
+            errors[0].fix.as_deref()
+        );
+        // Every prefix of broken code is still covered by tokens.
+        let code = "/** {@code x */ @A(\"a\\u 'b ``` \"\"\"\n 0x_.e+ 1e999f <T> a >>>= #ж";
+        for (i, _) in code.char_indices() {
+            let tokens = l.lex(&code[..i], ParserContext::new());
+            assert_eq!(i, tokens.iter().map(|t| t.len).sum::<usize>());
+        }
+    }
+
+    #[test]
+    fn lexer_diagnostics() {
+        let l = Lexer::new();
+        let tokens = l.lex("x =\n  0x_1L;", ParserContext::new());
+        let diagnostics: Vec<&Diagnostic> = tokens
+            .iter()
+            .filter_map(|t| t.diagnostic.as_ref())
+            .collect();
+        assert_eq!(1, diagnostics.len());
+        assert_eq!(DiagnosticKind::IllegalUnderscore, diagnostics[0].kind);
+        assert_eq!(
+            Some(Span {
+                start: 6,
+                end: 11,
+                start_pos: position(1, 2, 2),
+                end_pos: position(1, 7, 7),
+            }),
+            diagnostics[0].span
+        );
+    }
+
+    fn position(line: usize, column: usize, utf16_column: usize) -> Position {
+        Position {
+            line,
+            column,
+            utf16_column,
+        }
+    }
+
+    #[test]
+    fn lexer_spans() {
+        let l = Lexer::new();
+        let tokens = l.lex("/* ж\r\n*/ a\rb\n\"𝄞\" c", ParserContext::new());
+        let spans: Vec<Span> = tokens.iter().map(|t| t.span.unwrap()).collect();
+        assert_eq!(
+            Span {
+                start: 0,
+                end: 9,
+                start_pos: position(0, 0, 0),
+                end_pos: position(1, 2, 2),
+            },
+            spans[0]
+        );
+        assert_eq!(
+            Span {
+                start: 10,
+                end: 11,
+                start_pos: position(1, 3, 3),
+                end_pos: position(1, 4, 4),
+            },
+            spans[2]
+        );
+        assert_eq!(
+            Span {
+                start: 12,
+                end: 13,
+                start_pos: position(2, 0, 0),
+                end_pos: position(2, 1, 1),
+            },
+            spans[4]
+        );
+        assert_eq!(
+            Span {
+                start: 15,
+                end: 19,
+                start_pos: position(3, 1, 1),
+                end_pos: position(3, 5, 3),
+            },
+            spans[7]
+        );
+        assert_eq!(position(3, 8, 6), spans[10].end_pos);
+        for pair in spans.windows(2) {
+            assert_eq!(pair[0].end, pair[1].start);
+            assert_eq!(pair[0].end_pos, pair[1].start_pos);
+        }
+    }
+
+    #[test]
+    fn lexer_unicode_escapes() {
+        let l = Lexer::new();
+        let code = r"s = \u0022a\u0022; // x\u000aint\u0020y;";
+        let tokens = l.lex(code, ParserContext::new());
+        assert_eq!(
+            vec![
+                ("identifier", 1),
+                ("whitespace", 1),
+                ("operator.assignment", 1),
+                ("whitespace", 1),
+                ("string.quote", 6),
+                ("string", 1),
+                ("string.quote", 6),
+                ("separator.semicolon", 1),
+                ("whitespace", 1),
+                ("comment.line", 4),
+                ("line_terminator", 6),
+                ("keyword.type", 3),
+                ("whitespace", 6),
+                ("identifier", 1),
+                ("separator.semicolon", 1),
+            ],
+            names(&tokens)
+        );
+        assert_eq!(code.len(), tokens.iter().map(|t| t.len).sum::<usize>());
+        assert_eq!(
+            Span {
+                start: 11,
+                end: 17,
+                start_pos: position(0, 11, 11),
+                end_pos: position(0, 17, 17),
+            },
+            tokens[6].span.unwrap()
+        );
+        // A translated backslash does not start another escape.
+        let tokens = l.lex(r#""\u005c\u005cn""#, ParserContext::new());
+        assert_eq!(
+            vec![
+                ("string.quote", 1),
+                ("string.escape", 12),
+                ("string", 1),
+                ("string.quote", 1)
+            ],
+            names(&tokens)
+        );
+        // Lone surrogates are valid in strings and characters.
+        let tokens = l.lex(r#""\uD800""#, ParserContext::new());
+        assert_eq!(
+            vec![("string.quote", 1), ("string", 6), ("string.quote", 1)],
+            names(&tokens)
+        );
+        assert!(tokens.iter().all(|t| t.diagnostic.is_none()));
+        let tokens = l.lex(r"'\uDBFF'", ParserContext::new());
+        assert_eq!(
+            vec![("char.quote", 1), ("char", 6), ("char.quote", 1)],
+            names(&tokens)
+        );
+        assert!(tokens.iter().all(|t| t.diagnostic.is_none()));
+        let tokens = l.lex(r"x = 0x_\u0031L;", ParserContext::new());
+        let diagnostic = tokens[4].diagnostic.as_ref().unwrap();
+        assert_eq!(10, diagnostic.len);
+        assert_eq!(0..10, diagnostic.fix.as_ref().unwrap().span);
+    }
+
+    /// Relexes `code` after `edit`, checking the tokens against lexing the edited code.
+    fn relex(code: &str, range: Range<usize>, text: &str) -> Relexed {
+        let l = Lexer::new();
+        let context = ParserContext::new();
+        let mut lexed = l.lex_incremental(code, context);
+        let relexed = l.relex(&mut lexed, &Edit::new(range, text)).unwrap();
+        assert_eq!(
+            l.lex(lexed.code(), context),
+            lexed.tokens(),
+            "{:?}",
+            lexed.code()
+        );
+        let restarts = l.lex_incremental(lexed.code(), context).restarts;
+        assert_eq!(restarts, lexed.restarts, "{:?}", lexed.code());
+        relexed
+    }
+
+    #[test]
+    fn lexer_relex() {
+        let code = "class A {\r\n    int x = 1;\n    /* a */ String s = \"b\";\n    int y;\n}\n";
+        // Renaming `x` only lexes from the `{` before it up to the next token again.
+        assert_eq!(
+            Relexed {
+                tokens: 5..11,
+                replaced: 6
+            },
+            relex(code, 20..20, "yz")
+        );
+        // Opening a block comment swallows tokens up to the next `*/`.
+        assert_eq!(
+            Relexed {
+                tokens: 5..8,
+                replaced: 14
+            },
+            relex(code, 11..11, "/*")
+        );
+        // Unclosed, it swallows everything to the end.
+        let start = code.find("/*").unwrap();
+        assert_eq!(
+            Relexed {
+                tokens: 15..18,
+                replaced: 23
+            },
+            relex(code, start + 5..start + 7, "")
+        );
+        for (range, text) in [
+            (0..0, "\"\"\"\n"),
+            (start + 4..start + 4, "*/"),
+            (0..5, "record"),
+            (10..11, ""),
+            (9..11, "\r"),
+            (code.len()..code.len(), "int z"),
+            (code.len() - 1..code.len(), ""),
+            (30..40, "\\u0022\\u002F*"),
+            (15..30, "\n\n"),
+        ] {
+            relex(code, range, text);
+        }
+        // Lookahead goes over lines, so edits change how the lines before them lex.
+        for (code, range, text) in [
+            ("record\n  Foo(int x) {}\n", 9..19, "= 5;"),
+            ("x = 1;\nvar\n  y = 1;\n", 13..14, "="),
+            ("Map<String,\n  List<Integer>> m;\n", 14..29, "x;"),
+        ] {
+            relex(code, range, text);
+        }
+        // Only the code up to the next `;` is lexed again, even in a long file, but an
+        // unclosed comment still swallows all of it.
+        let code = "int a;\n".repeat(1000);
+        assert_eq!(
+            Relexed {
+                tokens: 0..4,
+                replaced: 4
+            },
+            relex(&code, 4..5, "bc")
+        );
+        assert_eq!(
+            Relexed {
+                tokens: 0..3,
+                replaced: 5000
+            },
+            relex(&code, 4..4, "/*")
+        );
+        // A `\r` and an inserted `\n` become a single line terminator.
+        assert_eq!(
+            Relexed {
+                tokens: 0..3,
+                replaced: 3
+            },
+            relex("a\rb", 2..2, "\n")
+        );
+        let code =
+            "@A(x = \"*/\") int a<b>c;\r\n/* \"\"\" */ var s = \"\"\"\n  x\"\"\"; // é\n-1\n";
+        let boundaries = (0..=code.len()).filter(|&i| code.is_char_boundary(i));
+        for (i, j) in boundaries.clone().zip(boundaries.skip(1)) {
+            for text in ["/*", "*/", "\"", "\"\"\"", "\n", "\r", "<", "@", "x", " "] {
+                relex(code, i..i, text);
+            }
+            relex(code, i..j, "");
+        }
+        // Invalid edits are rejected without changing anything.
+        let l = Lexer::new();
+        let mut lexed = l.lex_incremental("é = 1;", ParserContext::new());
+        let reversed = Range { start: 3, end: 2 };
+        for (range, error) in [
+            (3..9, EditError::OutOfBounds(3..9)),
+            (reversed.clone(), EditError::OutOfBounds(reversed)),
+            (1..1, EditError::NotCharBoundary(1..1)),
+        ] {
+            assert_eq!(Err(error), l.relex(&mut lexed, &Edit::new(range, "x")));
+        }
+        assert_eq!("é = 1;", lexed.code());
+        assert_eq!(l.lex("é = 1;", ParserContext::new()), lexed.tokens());
+    }
+
+    /// The kind of token each byte of `tokens` is in.
+    fn kinds(tokens: &[Token]) -> Vec<TokenKind> {
+        tokens
+            .iter()
+            .flat_map(|t| std::iter::repeat_n(t.kind, t.len))
+            .collect()
+    }
+
+    #[test]
+    fn lexer_lines() {
+        let l = Lexer::new();
+        let code = "/** Sums.\r\n * @param a {@code x}\r\n * b */\r\nMap<String, List<int[]>> m = f(\"\"\"\n  a /* \\\n  \"\"\" + 1, /* TODO\n*/ '\"');\n";
+        let mut state = LineState::default();
+        let mut states = vec![];
+        let mut tokens = vec![];
+        for line in code.split_inclusive('\n') {
+            let (line_tokens, next) = l.lex_line(&state, line);
+            assert_eq!(line.len(), line_tokens.iter().map(|t| t.len).sum::<usize>());
+            assert_eq!(Position::default(), line_tokens[0].span.unwrap().start_pos);
+            assert!(line_tokens.iter().all(|t| t.diagnostic.is_none()));
+            // Lexing a line again in the same state gives the same tokens.
+            let again = l.lex_line(&state, line);
+            assert_eq!(line_tokens, again.0);
+            assert_eq!(next, again.1);
+            tokens.extend(line_tokens);
+            states.push(state);
+            state = next;
+        }
+        assert_eq!(kinds(&l.lex(code, ParserContext::new())), kinds(&tokens));
+        assert_eq!(LineState::default(), states[3]);
+        assert_ne!(states[3], states[4]);
+        assert_eq!(
+            vec![
+                ("comment.doc", 3),
+                ("comment.doc.tag", 6),
+                ("comment.doc", 1)
+            ],
+            names(&l.lex_line(&states[1], " * @param a\n").0)[..3]
+        );
+        let (tokens, _) = l.lex_line(&states[5], "  \"\"\" + 1, /* TODO\n");
+        assert_eq!(("string", 2), names(&tokens)[0]);
+        assert_eq!(("string.quote", 3), names(&tokens)[1]);
+        assert_eq!(state, LineState::default());
+        // A line starting with `/` does not close the comment it continues, and strings
+        // do not continue on the next line.
+        for code in ["/**\n/ not closed\n*/ int x;\n", "s = \"a\\\nb\";\n"] {
+            let mut state = LineState::default();
+            let mut tokens = vec![];
+            for line in code.split_inclusive('\n') {
+                let (line_tokens, next) = l.lex_line(&state, line);
+                tokens.extend(line_tokens);
+                state = next;
+            }
+            assert_eq!(kinds(&l.lex(code, ParserContext::new())), kinds(&tokens));
+        }
+        // The indent of a text block is not known from its first line.
+        let (tokens, _) = l.lex_line(&LineState::default(), "s = \"\"\"\n");
+        assert_eq!(("string.quote", 3), names(&tokens)[4]);
+        assert!(tokens[4].metadata.is_empty());
+    }
+}
@@ -1,29 +1,166 @@
+use std::collections::HashMap;
+
 use lazy_static::lazy_static;
-use regex::{Match, Regex};
-
-use crate::parser::{Parser, ParserContext, Token};
-
-fn is_int_in_bounds(sign: Option<Match>, number: Match, suffix: Option<Match>, radix: u32) -> bool {
-    let number = &number.as_str().replace("_", "");
-    match suffix {
-        Some(_) => {
-            if let Some(sign) = sign {
-                i64::from_str_radix(&(String::from(&sign.as_str()[..1]) + number), radix).is_ok()
-            } else {
-                i64::from_str_radix(number, radix).is_ok()
-            }
+use regex::Match;
+
+use crate::hashmap;
+use crate::parser::{
+    pattern::Pattern, whitespace::whitespace, Diagnostic, DiagnosticKind, Fix, NumberPart, Parser,
+    ParserContext, Token, TokenKind, Value,
+};
+
+/// Digit groups may not start or end with an underscore. Octal digits follow the `0`
+/// prefix directly, so they are allowed to start with one.
+fn check_underscores(groups: &[Option<Match>], leading_allowed: bool) -> Option<DiagnosticKind> {
+    groups
+        .iter()
+        .flatten()
+        .map(|g| g.as_str())
+        .find(|g| g.ends_with('_') || (g.starts_with('_') && !leading_allowed))
+        .map(|_| DiagnosticKind::IllegalUnderscore)
+}
+
+/// Decoded values and diagnostics are what the number parsers derive from a literal.
+type Decoded = Result<HashMap<String, Value>, DiagnosticKind>;
+
+fn decode_int(
+    sign: Option<Match>,
+    after_unary_minus: bool,
+    number: Match,
+    suffix: Option<Match>,
+    radix: u32,
+) -> Decoded {
+    if number.as_str().is_empty() {
+        return match radix {
+            2 => Err(DiagnosticKind::InvalidBinaryNumber),
+            _ => Err(DiagnosticKind::InvalidHexNumber),
+        };
+    }
+    // The regexes take any decimal digit, so that `09` is one literal with a bad digit
+    // rather than `0` followed by `9`.
+    if number
+        .as_str()
+        .chars()
+        .any(|c| c != '_' && !c.is_digit(radix))
+    {
+        return match radix {
+            2 => Err(DiagnosticKind::IllegalBinaryDigit),
+            _ => Err(DiagnosticKind::IllegalOctalDigit),
+        };
+    }
+    if let Some(kind) = check_underscores(&[Some(number)], radix == 8) {
+        return Err(kind);
+    }
+    // JLS 3.10.1: decimal literals must fit the signed range, except for the one equal
+    // to `MIN_VALUE`'s magnitude, which may only appear as the operand of unary minus.
+    // Hexadecimal, octal and binary literals may use all the bits of the type.
+    let (max_signed, max_unsigned) = match suffix {
+        Some(_) => (i64::MAX as u128, u64::MAX as u128),
+        None => (i32::MAX as u128, u32::MAX as u128),
+    };
+    let negated = sign.is_some_and(|s| s.as_str() == "-");
+    let value = u128::from_str_radix(&number.as_str().replace("_", ""), radix)
+        .map_err(|_| DiagnosticKind::IntNumberTooLarge)?;
+    let in_bounds = match radix {
+        10 => value <= max_signed || ((negated || after_unary_minus) && value == max_signed + 1),
+        _ => value <= max_unsigned,
+    };
+    if !in_bounds {
+        return Err(DiagnosticKind::IntNumberTooLarge);
+    }
+    // Two's complement wrapping gives `0xFFFF_FFFF` its value of -1.
+    let value = if negated { value.wrapping_neg() } else { value };
+    let value = match suffix {
+        Some(_) => Value::Long(value as u64 as i64),
+        None => Value::Int(value as u32 as i32),
+    };
+    Ok(hashmap!(
+        String::from("value"),
+        value,
+        String::from("radix"),
+        Value::Int(radix as i32)
+    ))
+}
+
+/// Multiplies the little-endian base 2^32 number `n` by `factor^power`.
+fn mul_pow(n: &mut Vec<u32>, factor: u64, power: u64) {
+    for _ in 0..power {
+        let mut carry = 0;
+        for limb in n.iter_mut() {
+            let product = *limb as u64 * factor + carry;
+            *limb = product as u32;
+            carry = product >> 32;
         }
-        None => {
-            if let Some(sign) = sign {
-                i32::from_str_radix(&(String::from(&sign.as_str()[..1]) + number), radix).is_ok()
-            } else {
-                i32::from_str_radix(number, radix).is_ok()
+        if carry > 0 {
+            n.push(carry as u32);
+        }
+    }
+}
+
+/// Whether `digits × 10^exp10` is exactly `value`, which must be finite.
+fn is_exact(digits: &str, exp10: i64, value: f64) -> bool {
+    let digits = digits.trim_start_matches('0');
+    if value == 0.0 || digits.is_empty() {
+        return value == 0.0 && digits.is_empty();
+    }
+    // `value` is `mantissa × 2^exp2`, compare both sides as big integers.
+    let bits = value.abs().to_bits();
+    let (mantissa, exp2) = match (bits >> 52) as i64 {
+        0 => (bits, -1074),
+        biased => ((bits & ((1 << 52) - 1)) | (1 << 52), biased - 1075),
+    };
+    let mut lhs = vec![0];
+    for digit in digits.bytes() {
+        mul_pow(&mut lhs, 10, 1);
+        let (sum, carry) = lhs[0].overflowing_add((digit - b'0') as u32);
+        lhs[0] = sum;
+        let mut i = 1;
+        let mut carry = carry;
+        while carry {
+            if i == lhs.len() {
+                lhs.push(0);
             }
+            (lhs[i], carry) = lhs[i].overflowing_add(1);
+            i += 1;
+        }
+    }
+    let mut rhs = vec![mantissa as u32, (mantissa >> 32) as u32];
+    mul_pow(&mut lhs, 5, exp10.max(0) as u64);
+    mul_pow(&mut rhs, 5, (-exp10).max(0) as u64);
+    mul_pow(&mut lhs, 2, (exp10 - exp2).max(0) as u64);
+    mul_pow(&mut rhs, 2, (exp2 - exp10).max(0) as u64);
+    for n in [&mut lhs, &mut rhs] {
+        while n.last() == Some(&0) {
+            n.pop();
         }
     }
+    lhs == rhs
+}
+
+fn is_float(suffix: Option<Match>) -> bool {
+    suffix.is_some_and(|s| s.as_str().to_lowercase() == "f")
+}
+
+/// The metadata of a float literal, `exact` telling whether `value` is exactly the
+/// number written in the source rather than the nearest one the type can represent.
+fn float_metadata(value: f64, float: bool, radix: i32, exact: bool) -> HashMap<String, Value> {
+    let mut metadata = hashmap!(
+        String::from("value"),
+        if float {
+            Value::Float(value as f32)
+        } else {
+            Value::Double(value)
+        },
+        String::from("radix"),
+        Value::Int(radix)
+    );
+    if exact {
+        metadata.insert(String::from("exact"), Value::Int(1));
+    }
+    metadata
 }
 
-fn is_float_in_bounds(
+fn decode_float(
     sign: Option<Match>,
     whole: Option<Match>,
     frac: Option<Match>,
@@ -31,34 +168,153 @@ fn is_float_in_bounds(
     exp_sign: Option<Match>,
     exp_power: Option<Match>,
     suffix: Option<Match>,
-) -> bool {
+) -> Decoded {
+    if let Some(kind) = check_underscores(&[whole, frac, exp_power], false) {
+        return Err(kind);
+    }
+    if e.is_some() && exp_power.is_none_or(|p| p.as_str().is_empty()) {
+        return Err(DiagnosticKind::MalformedFpLiteral);
+    }
+    let whole = whole.map_or(String::new(), |w| w.as_str().replace("_", ""));
+    let frac = frac.map_or(String::new(), |f| f.as_str().replace("_", ""));
     let mut num = String::new();
     if let Some(sign) = sign {
         num += sign.as_str();
     }
-    if let Some(whole) = whole {
-        num += &whole.as_str().replace("_", "");
-    }
-    if let Some(frac) = frac {
+    num += &whole;
+    if !frac.is_empty() {
         num += ".";
-        num += &frac.as_str().replace("_", "");
+        num += &frac;
     }
+    let mut exp = String::new();
     if e.is_some() {
-        num += "e";
         if let Some(exp_sign) = exp_sign {
-            num += exp_sign.as_str();
+            exp += exp_sign.as_str();
+        }
+        exp += &exp_power.unwrap().as_str().replace("_", "");
+        num += "e";
+        num += &exp;
+    }
+    let float = is_float(suffix);
+    let value = if float {
+        num.parse::<f32>().unwrap() as f64
+    } else {
+        num.parse::<f64>().unwrap()
+    };
+    let digits = whole + &frac;
+    if value.is_infinite() {
+        Err(DiagnosticKind::FpNumberTooLarge)
+    } else if value == 0.0 && digits.chars().any(|c| c != '0') {
+        Err(DiagnosticKind::FpNumberTooSmall)
+    } else {
+        // The exponent fits when the value is finite and non-zero, and is unused otherwise.
+        let exp10 = exp.parse::<i64>().unwrap_or(0) - frac.len() as i64;
+        let exact = is_exact(&digits, exp10, value);
+        Ok(float_metadata(value, float, 10, exact))
+    }
+}
+
+/// Multiplies `value` by `2^exp` without overflowing the intermediate powers of two.
+fn scale(mut value: f64, mut exp: i64) -> f64 {
+    while exp != 0 {
+        let step = exp.clamp(-1000, 1000);
+        value *= f64::from_bits(((1023 + step) as u64) << 52);
+        exp -= step;
+    }
+    value
+}
+
+fn decode_hex_float(
+    sign: Option<Match>,
+    whole: Option<Match>,
+    frac: Option<Match>,
+    exp_sign: Option<Match>,
+    exp_power: Match,
+    suffix: Option<Match>,
+) -> Decoded {
+    if let Some(kind) = check_underscores(&[whole, frac, Some(exp_power)], false) {
+        return Err(kind);
+    }
+    if exp_power.as_str().is_empty() {
+        return Err(DiagnosticKind::MalformedFpLiteral);
+    }
+    let float = is_float(suffix);
+    let (precision, max_exp, min_exp) = match float {
+        true => (24, 127, -149),
+        false => (53, 1023, -1074),
+    };
+    let frac = frac.map_or(String::new(), |f| f.as_str().replace("_", ""));
+    let mut digits = whole.map_or(String::new(), |w| w.as_str().replace("_", ""));
+    digits += &frac;
+    let digits = digits.trim_start_matches('0');
+    let negated = sign.is_some_and(|s| s.as_str() == "-");
+    let first = match digits.chars().next() {
+        Some(first) => first.to_digit(16).unwrap(),
+        None => {
+            let zero = if negated { -0.0 } else { 0.0 };
+            return Ok(float_metadata(zero, float, 16, true));
         }
-        num += &exp_power.unwrap().as_str().replace("_", "");
+    };
+    let mut exp = exp_power
+        .as_str()
+        .replace("_", "")
+        .parse::<i64>()
+        .unwrap_or(i64::MAX / 2);
+    if exp_sign.is_some_and(|s| s.as_str() == "-") {
+        exp = -exp;
     }
-    println!("Parsing: {}", num);
-    match suffix {
-        Some(s) if s.as_str().to_lowercase() == "f" => num.parse::<f32>().is_ok(),
-        _ => num.parse::<f64>().is_ok(),
+    // Binary exponent of the lowest digit of the value.
+    let unit = exp.saturating_sub(4 * frac.len() as i64);
+    // Binary exponent of the highest set bit of the value.
+    let top =
+        (4 * (digits.len() as i64 - 1) + 31 - first.leading_zeros() as i64).saturating_add(unit);
+    // Halfway below the smallest subnormal only rounds to zero when nothing else is set.
+    let power_of_two = first.is_power_of_two() && digits[1..].chars().all(|c| c == '0');
+    if top > max_exp {
+        return Err(DiagnosticKind::FpNumberTooLarge);
+    } else if top < min_exp - 1 || (top == min_exp - 1 && power_of_two) {
+        return Err(DiagnosticKind::FpNumberTooSmall);
     }
+    let significant = digits.trim_end_matches('0');
+    let last = significant.chars().last().unwrap().to_digit(16).unwrap();
+    let bottom =
+        4 * (digits.len() - significant.len()) as i64 + last.trailing_zeros() as i64 + unit;
+    let exact = top - bottom < precision && bottom >= min_exp;
+    // Fifteen digits hold more bits than a double, the rest only matter for rounding.
+    let (head, tail) = digits.split_at(digits.len().min(15));
+    let sticky = tail.chars().any(|c| c != '0') as u64;
+    let mantissa = u64::from_str_radix(head, 16).unwrap() << 1 | sticky;
+    let exp = unit + 4 * tail.len() as i64 - 1;
+    // Round to the precision of the type here, as going through a double first would
+    // round `f` literals twice.
+    let shift = (64 - mantissa.leading_zeros() as i64 - precision).max(min_exp - exp);
+    let mantissa = if shift > 0 {
+        let (kept, rest, half) = (mantissa >> shift, mantissa << (64 - shift), 1 << 63);
+        kept + (rest > half || (rest == half && kept & 1 == 1)) as u64
+    } else {
+        mantissa
+    };
+    let mut value = scale(mantissa as f64, exp + shift.max(0));
+    // The largest values round up past the largest finite one.
+    if value.is_infinite() || (float && (value as f32).is_infinite()) {
+        return Err(DiagnosticKind::FpNumberTooLarge);
+    }
+    if negated {
+        value = -value;
+    }
+    Ok(float_metadata(value, float, 16, exact))
+}
+
+fn non_empty(m: Option<Match>) -> Option<Match> {
+    m.filter(|m| !m.as_str().is_empty())
 }
 
+/// Tokenizes the parts of a number literal. The decoded metadata, or the diagnostic
+/// covering the rest of the literal, is attached to the first token after the sign.
+#[allow(clippy::too_many_arguments)]
 fn tokenize_matches(
     condition: bool,
+    decoded: Decoded,
     sign: Option<Match>,
     whitespaces: Option<Match>,
     prefix_len: Option<usize>,
@@ -78,19 +334,29 @@ fn tokenize_matches(
         tokens.push(Token::number_sign());
     }
     if let Some(ws) = whitespaces {
-        tokens.push(Token::whitespace(ws.as_str().len()));
+        tokens.extend(whitespace(ws.as_str()));
     }
+    let literal_start = tokens.len();
     if let Some(prefix_len) = prefix_len {
-        tokens.push(Token::new("number.prefix", prefix_len));
+        tokens.push(Token::new(
+            TokenKind::Number(NumberPart::Prefix),
+            prefix_len,
+        ));
     }
-    if let Some(whole) = whole {
-        tokens.push(Token::new("number", whole.as_str().len()));
+    if let Some(whole) = non_empty(whole) {
+        tokens.push(Token::new(
+            TokenKind::Number(NumberPart::Digits),
+            whole.as_str().len(),
+        ));
     }
     if dot {
         tokens.push(Token::number_dot());
     }
-    if let Some(frac) = fraction {
-        tokens.push(Token::new("number", frac.as_str().len()));
+    if let Some(frac) = non_empty(fraction) {
+        tokens.push(Token::new(
+            TokenKind::Number(NumberPart::Digits),
+            frac.as_str().len(),
+        ));
     }
     if e.is_some() {
         tokens.push(Token::number_e());
@@ -98,12 +364,35 @@ fn tokenize_matches(
     if exp_sign.is_some() {
         tokens.push(Token::number_e_sign());
     }
-    if let Some(exp_power) = exp_power {
-        tokens.push(Token::new("number.e.power", exp_power.as_str().len()));
+    if let Some(exp_power) = non_empty(exp_power) {
+        tokens.push(Token::new(
+            TokenKind::Number(NumberPart::ExponentPower),
+            exp_power.as_str().len(),
+        ));
     }
     if suffix.is_some() {
         tokens.push(Token::number_suffix());
     }
+    let kind = match decoded {
+        Ok(metadata) => {
+            tokens[literal_start].add_metadata(metadata);
+            return Some(tokens);
+        }
+        Err(kind) => kind,
+    };
+    let len = tokens[literal_start..].iter().map(|t| t.len).sum();
+    let message = match kind {
+        DiagnosticKind::IntNumberTooLarge => {
+            let digits = non_empty(whole).map_or("", |w| w.as_str());
+            format!("{}: {}", kind.message(), digits.replace("_", ""))
+        }
+        _ => String::from(kind.message()),
+    };
+    let token = tokens.remove(literal_start);
+    tokens.insert(
+        literal_start,
+        token.with_diagnostic(Diagnostic::new(kind, message, len)),
+    );
     Some(tokens)
 }
 
@@ -114,47 +403,77 @@ enum DigitsOrCustom<'a> {
 
 use DigitsOrCustom::{Custom, Digits};
 
-fn create_int_regex(prefix: &str, digits_or_custom: DigitsOrCustom) -> Regex {
-    let r = match digits_or_custom {
-        Digits(d) => Regex::new(&format!(
-            r"^([\+-])?(\s+)?{p}({d}(?:_?{d})*)([lL])?\b",
+fn create_int_regex(prefix: &str, digits_or_custom: DigitsOrCustom) -> Pattern {
+    match digits_or_custom {
+        Digits(d) => Pattern::new(&format!(
+            r"^([\+-])?([ \t\x0C\r\n]+)?{p}([{d}_]*)([lL])?\b",
             p = prefix,
             d = d
         )),
-        Custom(c) => Regex::new(&format!(
-            r"^([\+-])?(\s+)?{p}({c})([lL])?\b",
+        Custom(c) => Pattern::new(&format!(
+            r"^([\+-])?([ \t\x0C\r\n]+)?{p}({c})([lL])?\b",
             p = prefix,
             c = c
         )),
-    };
-    match r {
-        Ok(r) => r,
-        Err(e) => panic!(e),
     }
 }
 
-fn create_float_regex(middle_pat: &str) -> Regex {
-    let r = Regex::new(&format!(
-        r"^([\+-])?(\s+)?{m}([eE]([\+-])?(\d(?:_?\d)*))?([dDfF])?\b",
+fn create_float_regex(middle_pat: &str) -> Pattern {
+    Pattern::new(&format!(
+        r"^([\+-])?([ \t\x0C\r\n]+)?{m}([eE]([\+-])?([\d_]*))?([dDfF])?\b",
         m = middle_pat
             .replace("d", r"\.")
             .replace("o", r"(\.)?")
-            .replace("n", r"(\d(?:_?\d)*)")
-    ));
-    match r {
-        Ok(r) => r,
-        Err(e) => panic!(e),
-    }
+            .replace("n", r"(\d[\d_]*)")
+            .replace("f", r"([\d_]+)")
+    ))
+}
+
+fn create_hex_float_regex() -> Pattern {
+    Pattern::new(&format!(
+        r"^([\+-])?([ \t\x0C\r\n]+)?0[xX]({h})?(\.)?({h})?([pP])([\+-])?([\d_]*)([dDfF])?\b",
+        h = r"[\da-fA-F_]+"
+    ))
 }
 
 lazy_static! {
-    static ref BIN_INT_REGEX: Regex = create_int_regex("0[bB]", Digits(r"[01]"));
-    static ref OCT_INT_REGEX: Regex = create_int_regex("0", Custom(r"(?:_?[0-7])*"));
-    static ref DEC_INT_REGEX: Regex = create_int_regex("", Custom(r"0|[1-9](?:_?\d)*"));
-    static ref HEX_INT_REGEX: Regex = create_int_regex("0[xX]", Digits(r"[\da-fA-F]"));
-    static ref FULL_FLOAT_REGEX: Regex = create_float_regex("ndn");
-    static ref FRAC_FLOAT_REGEX: Regex = create_float_regex("dn");
-    static ref WHOLE_FLOAT_REGEX: Regex = create_float_regex("no");
+    static ref BIN_INT_REGEX: Pattern = create_int_regex("0[bB]", Digits(r"\d"));
+    static ref OCT_INT_REGEX: Pattern = create_int_regex("0", Custom(r"[\d_]+"));
+    static ref DEC_INT_REGEX: Pattern = create_int_regex("", Custom(r"0|[1-9][\d_]*"));
+    static ref HEX_INT_REGEX: Pattern = create_int_regex("0[xX]", Digits(r"\da-fA-F"));
+    static ref FULL_FLOAT_REGEX: Pattern = create_float_regex("ndf");
+    static ref FRAC_FLOAT_REGEX: Pattern = create_float_regex("dn");
+    static ref WHOLE_FLOAT_REGEX: Pattern = create_float_regex("no");
+    static ref HEX_FLOAT_REGEX: Pattern = create_hex_float_regex();
+}
+
+/// A fix for the problem of kind `kind` with the literal `literal`, suffix included.
+fn fix(kind: DiagnosticKind, literal: &str) -> Option<Fix> {
+    match kind {
+        DiagnosticKind::IntNumberTooLarge if !literal.ends_with(['l', 'L']) => {
+            let digits = literal.replace("_", "");
+            let (digits, radix) = match digits.get(..2) {
+                Some("0x" | "0X") => (&digits[2..], 16),
+                Some("0b" | "0B") => (&digits[2..], 2),
+                _ if digits.len() > 1 && digits.starts_with('0') => (&digits[1..], 8),
+                _ => (&digits[..], 10),
+            };
+            let value = u64::from_str_radix(digits, radix).ok()?;
+            (radix != 10 || value <= i64::MAX as u64).then(|| {
+                Fix::new(
+                    "Make it a long literal",
+                    literal.len()..literal.len(),
+                    String::from("L"),
+                )
+            })
+        }
+        DiagnosticKind::IllegalUnderscore => Some(Fix::new(
+            "Remove the underscores",
+            0..literal.len(),
+            literal.replace("_", ""),
+        )),
+        _ => None,
+    }
 }
 
 struct BinIntParser;
@@ -166,7 +485,7 @@ impl BinIntParser {
 }
 
 impl Parser for BinIntParser {
-    fn parse(&self, code: &str, _: ParserContext) -> Option<Vec<Token>> {
+    fn parse(&self, code: &str, context: ParserContext) -> Option<Vec<Token>> {
         BIN_INT_REGEX.captures(code).and_then(|captures| {
             let (sign, ws, number, suffix) = (
                 captures.get(1),
@@ -175,7 +494,14 @@ impl Parser for BinIntParser {
                 captures.get(4),
             );
             tokenize_matches(
-                is_int_in_bounds(sign, number.unwrap(), suffix, 2),
+                true,
+                decode_int(
+                    sign,
+                    context.is_after_unary_minus(),
+                    number.unwrap(),
+                    suffix,
+                    2,
+                ),
                 sign,
                 ws,
                 Some(2),
@@ -200,7 +526,7 @@ impl OctIntParser {
 }
 
 impl Parser for OctIntParser {
-    fn parse(&self, code: &str, _: ParserContext) -> Option<Vec<Token>> {
+    fn parse(&self, code: &str, context: ParserContext) -> Option<Vec<Token>> {
         OCT_INT_REGEX.captures(code).and_then(|captures| {
             let (sign, ws, number, suffix) = (
                 captures.get(1),
@@ -209,7 +535,14 @@ impl Parser for OctIntParser {
                 captures.get(4),
             );
             tokenize_matches(
-                is_int_in_bounds(sign, number.unwrap(), suffix, 8),
+                true,
+                decode_int(
+                    sign,
+                    context.is_after_unary_minus(),
+                    number.unwrap(),
+                    suffix,
+                    8,
+                ),
                 sign,
                 ws,
                 Some(1),
@@ -234,7 +567,7 @@ impl DecIntParser {
 }
 
 impl Parser for DecIntParser {
-    fn parse(&self, code: &str, _: ParserContext) -> Option<Vec<Token>> {
+    fn parse(&self, code: &str, context: ParserContext) -> Option<Vec<Token>> {
         DEC_INT_REGEX.captures(code).and_then(|captures| {
             let (sign, ws, number, suffix) = (
                 captures.get(1),
@@ -243,7 +576,14 @@ impl Parser for DecIntParser {
                 captures.get(4),
             );
             tokenize_matches(
-                is_int_in_bounds(sign, number.unwrap(), suffix, 10),
+                true,
+                decode_int(
+                    sign,
+                    context.is_after_unary_minus(),
+                    number.unwrap(),
+                    suffix,
+                    10,
+                ),
                 sign,
                 ws,
                 None,
@@ -268,7 +608,7 @@ impl HexIntParser {
 }
 
 impl Parser for HexIntParser {
-    fn parse(&self, code: &str, _: ParserContext) -> Option<Vec<Token>> {
+    fn parse(&self, code: &str, context: ParserContext) -> Option<Vec<Token>> {
         HEX_INT_REGEX.captures(code).and_then(|captures| {
             let (sign, ws, number, suffix) = (
                 captures.get(1),
@@ -277,7 +617,14 @@ impl Parser for HexIntParser {
                 captures.get(4),
             );
             tokenize_matches(
-                is_int_in_bounds(sign, number.unwrap(), suffix, 16),
+                true,
+                decode_int(
+                    sign,
+                    context.is_after_unary_minus(),
+                    number.unwrap(),
+                    suffix,
+                    16,
+                ),
                 sign,
                 ws,
                 Some(2),
@@ -315,7 +662,8 @@ impl Parser for FullFloatParser {
                 captures.get(8),
             );
             tokenize_matches(
-                is_float_in_bounds(sign, whole, frac, e, exp_sign, exp_power, suffix),
+                true,
+                decode_float(sign, whole, frac, e, exp_sign, exp_power, suffix),
                 sign,
                 ws,
                 None,
@@ -352,7 +700,8 @@ impl Parser for FracFloatParser {
                 captures.get(7),
             );
             tokenize_matches(
-                is_float_in_bounds(sign, None, frac, e, exp_sign, exp_power, suffix),
+                true,
+                decode_float(sign, None, frac, e, exp_sign, exp_power, suffix),
                 sign,
                 ws,
                 None,
@@ -390,8 +739,8 @@ impl Parser for WholeFloatParser {
                 captures.get(8),
             );
             tokenize_matches(
-                is_float_in_bounds(sign, whole, None, e, exp_sign, exp_power, suffix)
-                    && (dot.is_some() || e.is_some() || suffix.is_some()),
+                dot.is_some() || e.is_some() || suffix.is_some(),
+                decode_float(sign, whole, None, e, exp_sign, exp_power, suffix),
                 sign,
                 ws,
                 None,
@@ -407,6 +756,47 @@ impl Parser for WholeFloatParser {
     }
 }
 
+struct HexFloatParser;
+
+impl HexFloatParser {
+    fn new() -> Self {
+        Self
+    }
+}
+
+impl Parser for HexFloatParser {
+    fn parse(&self, code: &str, _: ParserContext) -> Option<Vec<Token>> {
+        HEX_FLOAT_REGEX.captures(code).and_then(|captures| {
+            let (sign, ws, whole, dot, frac, p, exp_sign, exp_power, suffix) = (
+                captures.get(1),
+                captures.get(2),
+                captures.get(3),
+                captures.get(4),
+                captures.get(5),
+                captures.get(6),
+                captures.get(7),
+                captures.get(8),
+                captures.get(9),
+            );
+            tokenize_matches(
+                whole.is_some() || frac.is_some(),
+                decode_hex_float(sign, whole, frac, exp_sign, exp_power.unwrap(), suffix),
+                sign,
+                ws,
+                Some(2),
+                whole,
+                dot.is_some(),
+                frac,
+                // The binary exponent is tokenized the same way as a decimal one.
+                p,
+                exp_sign,
+                exp_power,
+                suffix,
+            )
+        })
+    }
+}
+
 pub struct NumberParser {
     parsers: Vec<Box<dyn Parser>>,
 }
@@ -418,6 +808,7 @@ impl NumberParser {
                 Box::new(BinIntParser::new()),
                 Box::new(OctIntParser::new()),
                 Box::new(DecIntParser::new()),
+                Box::new(HexFloatParser::new()),
                 Box::new(HexIntParser::new()),
                 Box::new(FullFloatParser::new()),
                 Box::new(FracFloatParser::new()),
@@ -427,39 +818,113 @@ impl NumberParser {
     }
 }
 
+impl Default for NumberParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Parser for NumberParser {
+    /// Picks the longest number any of the parsers recognizes, so that `1_.5` is
+    /// reported as a malformed float rather than an integer followed by `.5`.
     fn parse(&self, code: &str, context: ParserContext) -> Option<Vec<Token>> {
-        self.parsers
+        if !context.is_signed_numbers() && code.starts_with(['+', '-']) {
+            return None;
+        }
+        let len = |tokens: &Vec<Token>| tokens.iter().map(|t| t.len).sum::<usize>();
+        let mut tokens = self
+            .parsers
             .iter()
             .filter_map(|p| p.parse(code, context))
-            .nth(0)
+            .fold(None, |longest, tokens| match longest {
+                Some(longest) if len(&longest) >= len(&tokens) => Some(longest),
+                _ => Some(tokens),
+            })?;
+        let mut start = 0;
+        for token in &mut tokens {
+            if let Some(diagnostic) = token.diagnostic.take() {
+                let literal = &code[start..start + diagnostic.len];
+                token.diagnostic = Some(match fix(diagnostic.kind, literal) {
+                    Some(fix) => diagnostic.with_fix(fix),
+                    None => diagnostic,
+                });
+            }
+            start += token.len;
+        }
+        Some(tokens)
+    }
+
+    fn patterns(&self) -> Vec<&'static Pattern> {
+        vec![
+            &BIN_INT_REGEX,
+            &OCT_INT_REGEX,
+            &DEC_INT_REGEX,
+            &HEX_INT_REGEX,
+            &FULL_FLOAT_REGEX,
+            &FRAC_FLOAT_REGEX,
+            &WHOLE_FLOAT_REGEX,
+            &HEX_FLOAT_REGEX,
+        ]
+    }
+
+    fn expected(&self) -> Vec<TokenKind> {
+        vec![TokenKind::Number(NumberPart::Digits)]
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::super::{Parser, ParserContext, Token};
+    use super::super::{lexer::Lexer, DiagnosticKind::*, ErrorCode, Parser, ParserContext, Token};
     use super::*;
 
+    /// Most tests cover literals with their sign, as `number.sign` tokens.
+    fn signed() -> ParserContext {
+        ParserContext::new().with_signed_numbers(true)
+    }
+
+    /// The tokens without their metadata and fixes, which `number_values` and
+    /// `number_errors` cover.
+    fn tokens(p: &dyn Parser, code: &str) -> Option<Vec<Token>> {
+        let mut tokens = p.parse(code, signed())?;
+        for token in &mut tokens {
+            token.metadata.clear();
+            if let Some(diagnostic) = &mut token.diagnostic {
+                diagnostic.fix = None;
+            }
+        }
+        Some(tokens)
+    }
+
+    fn diagnostic(p: &dyn Parser, code: &str) -> Option<DiagnosticKind> {
+        p.parse(code, signed())
+            .unwrap()
+            .into_iter()
+            .find_map(|t| t.diagnostic)
+            .map(|d| d.kind)
+    }
+
     #[test]
     fn bin_int_parser() {
         let p = BinIntParser::new();
         assert_eq!(
             Some(vec![
-                Token::new("number.prefix", 2),
-                Token::new("number", 3),
+                Token::new(TokenKind::Number(NumberPart::Prefix), 2),
+                Token::new(TokenKind::Number(NumberPart::Digits), 3),
             ]),
-            p.parse(&"0b111", ParserContext::new())
+            tokens(&p, "0b111")
         );
         assert_eq!(
             Some(vec![
-                Token::new("number.prefix", 2),
-                Token::new("number", 7),
+                Token::new(TokenKind::Number(NumberPart::Prefix), 2),
+                Token::new(TokenKind::Number(NumberPart::Digits), 7),
             ]),
-            p.parse(&"0B111_111", ParserContext::new())
+            tokens(&p, "0B111_111")
         );
-        assert_eq!(None, p.parse(&"0b_1", ParserContext::new()));
-        assert_eq!(None, p.parse(&"0b111_", ParserContext::new()));
+        assert_eq!(Some(IllegalUnderscore), diagnostic(&p, "0b_1"));
+        assert_eq!(Some(IllegalUnderscore), diagnostic(&p, "0b111_"));
+        assert_eq!(Some(InvalidBinaryNumber), diagnostic(&p, "0b"));
+        assert_eq!(Some(IllegalBinaryDigit), diagnostic(&p, "0b2"));
+        assert_eq!(Some(IllegalBinaryDigit), diagnostic(&p, "0b1021"));
     }
 
     #[test]
@@ -467,46 +932,49 @@ mod tests {
         let p = OctIntParser::new();
         assert_eq!(
             Some(vec![
-                Token::new("number.prefix", 1),
-                Token::new("number", 2),
+                Token::new(TokenKind::Number(NumberPart::Prefix), 1),
+                Token::new(TokenKind::Number(NumberPart::Digits), 2),
             ]),
-            p.parse(&"017", ParserContext::new())
+            tokens(&p, "017")
         );
         assert_eq!(
             Some(vec![
-                Token::new("number.prefix", 1),
-                Token::new("number", 7),
+                Token::new(TokenKind::Number(NumberPart::Prefix), 1),
+                Token::new(TokenKind::Number(NumberPart::Digits), 7),
             ]),
-            p.parse(&"0123_456", ParserContext::new())
+            tokens(&p, "0123_456")
         );
         assert_eq!(
             Some(vec![
-                Token::new("number.prefix", 1),
-                Token::new("number", 8),
+                Token::new(TokenKind::Number(NumberPart::Prefix), 1),
+                Token::new(TokenKind::Number(NumberPart::Digits), 8),
             ]),
-            p.parse(&"0_123_456", ParserContext::new())
+            tokens(&p, "0_123_456")
         );
-        assert_eq!(None, p.parse(&"0", ParserContext::new()));
-        assert_eq!(None, p.parse(&"0_", ParserContext::new()));
-        assert_eq!(None, p.parse(&"0_123_", ParserContext::new()));
-        assert_eq!(None, p.parse(&"12", ParserContext::new()));
+        assert_eq!(None, tokens(&p, "0"));
+        assert_eq!(Some(IllegalUnderscore), diagnostic(&p, "0_"));
+        assert_eq!(Some(IllegalUnderscore), diagnostic(&p, "0_123_"));
+        assert_eq!(Some(IllegalOctalDigit), diagnostic(&p, "09"));
+        assert_eq!(Some(IllegalOctalDigit), diagnostic(&p, "0_9"));
+        assert_eq!(Some(IllegalOctalDigit), diagnostic(&p, "0128L"));
+        assert_eq!(None, tokens(&p, "12"));
     }
 
     #[test]
     fn dec_int_parser() {
         let p = DecIntParser::new();
         assert_eq!(
-            Some(vec![Token::new("number", 3)]),
-            p.parse(&"123", ParserContext::new())
+            Some(vec![Token::new(TokenKind::Number(NumberPart::Digits), 3)]),
+            tokens(&p, "123")
         );
         assert_eq!(
-            Some(vec![Token::new("number", 5)]),
-            p.parse(&"1_000", ParserContext::new())
+            Some(vec![Token::new(TokenKind::Number(NumberPart::Digits), 5)]),
+            tokens(&p, "1_000")
         );
-        assert_eq!(None, p.parse(&"12345678909876543", ParserContext::new()));
-        assert_eq!(None, p.parse(&"01", ParserContext::new()));
-        assert_eq!(None, p.parse(&"0_", ParserContext::new()));
-        assert_eq!(None, p.parse(&"123_", ParserContext::new()));
+        assert_eq!(Some(IntNumberTooLarge), diagnostic(&p, "12345678909876543"));
+        assert_eq!(None, tokens(&p, "01"));
+        assert_eq!(None, tokens(&p, "0_"));
+        assert_eq!(Some(IllegalUnderscore), diagnostic(&p, "123_"));
     }
 
     #[test]
@@ -514,13 +982,50 @@ mod tests {
         let p = HexIntParser::new();
         assert_eq!(
             Some(vec![
-                Token::new("number.prefix", 2),
-                Token::new("number", 3),
+                Token::new(TokenKind::Number(NumberPart::Prefix), 2),
+                Token::new(TokenKind::Number(NumberPart::Digits), 3),
             ]),
-            p.parse("0x9aF", ParserContext::new())
+            tokens(&p, "0x9aF")
         );
-        assert_eq!(None, p.parse("0x_A", ParserContext::new()));
-        assert_eq!(None, p.parse("0xf_", ParserContext::new()));
+        assert_eq!(Some(IllegalUnderscore), diagnostic(&p, "0x_A"));
+        assert_eq!(Some(IllegalUnderscore), diagnostic(&p, "0xf_"));
+        assert_eq!(Some(InvalidHexNumber), diagnostic(&p, "0x;"));
+    }
+
+    #[test]
+    fn int_bounds() {
+        let p = NumberParser::new();
+        for code in &[
+            "2147483647",
+            "-2147483648",
+            "- 2147483648",
+            "0x8000_0000",
+            "0xFFFF_FFFF",
+            "-0xFFFFFFFF",
+            "037777777777",
+            "0b1111_1111_1111_1111_1111_1111_1111_1111",
+            "9223372036854775807L",
+            "-9223372036854775808L",
+            "0xFFFF_FFFF_FFFF_FFFFL",
+            "01777777777777777777777L",
+            "0x7fff_ffff_ffff_ffffl",
+        ] {
+            assert_eq!(None, diagnostic(&p, code), "{}", code);
+        }
+        for code in &[
+            "2147483648",
+            "+2147483648",
+            "-2147483649",
+            "0x1_0000_0000",
+            "040000000000",
+            "0b1_0000_0000_0000_0000_0000_0000_0000_0000",
+            "9223372036854775808L",
+            "-9223372036854775809L",
+            "0x1_0000_0000_0000_0000L",
+            "1000000000000000000000000000000000000000000",
+        ] {
+            assert_eq!(Some(IntNumberTooLarge), diagnostic(&p, code), "{}", code);
+        }
     }
 
     #[test]
@@ -528,54 +1033,63 @@ mod tests {
         let p = FullFloatParser::new();
         assert_eq!(
             Some(vec![
-                Token::new("number", 1),
+                Token::new(TokenKind::Number(NumberPart::Digits), 1),
                 Token::number_dot(),
-                Token::new("number", 1),
+                Token::new(TokenKind::Number(NumberPart::Digits), 1),
             ]),
-            p.parse("1.0", ParserContext::new())
+            tokens(&p, "1.0")
         );
         assert_eq!(
             Some(vec![
-                Token::new("number", 7),
+                Token::new(TokenKind::Number(NumberPart::Digits), 7),
                 Token::number_dot(),
-                Token::new("number", 7),
+                Token::new(TokenKind::Number(NumberPart::Digits), 7),
                 Token::number_suffix(),
             ]),
-            p.parse("1_2_3_4.1_2_3_4f", ParserContext::new())
+            tokens(&p, "1_2_3_4.1_2_3_4f")
         );
         assert_eq!(
             Some(vec![
                 Token::number_sign(),
                 Token::whitespace(2),
-                Token::new("number", 3),
+                Token::new(TokenKind::Number(NumberPart::Digits), 3),
                 Token::number_dot(),
-                Token::new("number", 3),
+                Token::new(TokenKind::Number(NumberPart::Digits), 3),
                 Token::number_e(),
                 Token::number_e_sign(),
-                Token::new("number.e.power", 3),
+                Token::new(TokenKind::Number(NumberPart::ExponentPower), 3),
                 Token::number_suffix(),
             ]),
-            p.parse("-  1_0.1_0e+1_0d", ParserContext::new())
+            tokens(&p, "-  1_0.1_0e+1_0d")
         );
-        assert_eq!(None, p.parse("123_.4f", ParserContext::new()));
-        assert_eq!(None, p.parse("123._4f", ParserContext::new()));
-        assert_eq!(None, p.parse(".1", ParserContext::new()));
+        assert_eq!(Some(IllegalUnderscore), diagnostic(&p, "123_.4f"));
+        assert_eq!(Some(IllegalUnderscore), diagnostic(&p, "123._4f"));
+        assert_eq!(Some(IllegalUnderscore), diagnostic(&p, "1.0e_1"));
+        assert_eq!(Some(MalformedFpLiteral), diagnostic(&p, "1.0e;"));
+        assert_eq!(Some(FpNumberTooLarge), diagnostic(&p, "1.0e999"));
+        assert_eq!(Some(FpNumberTooLarge), diagnostic(&p, "3.5e38f"));
+        assert_eq!(Some(FpNumberTooSmall), diagnostic(&p, "1.0e-999"));
+        assert_eq!(None, diagnostic(&p, "0.0e-999"));
+        assert_eq!(None, tokens(&p, ".1"));
     }
 
     #[test]
     fn frac_float_parser() {
         let p = FracFloatParser::new();
         assert_eq!(
-            Some(vec![Token::number_dot(), Token::new("number", 1),]),
-            p.parse(".1", ParserContext::new())
+            Some(vec![
+                Token::number_dot(),
+                Token::new(TokenKind::Number(NumberPart::Digits), 1),
+            ]),
+            tokens(&p, ".1")
         );
         assert_eq!(
             Some(vec![
                 Token::number_dot(),
-                Token::new("number", 1),
+                Token::new(TokenKind::Number(NumberPart::Digits), 1),
                 Token::number_suffix(),
             ]),
-            p.parse(".1d", ParserContext::new())
+            tokens(&p, ".1d")
         );
     }
 
@@ -583,43 +1097,354 @@ mod tests {
     fn whole_float_parser() {
         let p = WholeFloatParser::new();
         assert_eq!(
-            Some(vec![Token::new("number", 1), Token::number_suffix(),]),
-            p.parse("1f", ParserContext::new())
+            Some(vec![
+                Token::new(TokenKind::Number(NumberPart::Digits), 1),
+                Token::number_suffix(),
+            ]),
+            tokens(&p, "1f")
+        );
+    }
+
+    #[test]
+    fn hex_float_parser() {
+        let p = HexFloatParser::new();
+        assert_eq!(
+            Some(vec![
+                Token::new(TokenKind::Number(NumberPart::Prefix), 2),
+                Token::new(TokenKind::Number(NumberPart::Digits), 1),
+                Token::number_dot(),
+                Token::new(TokenKind::Number(NumberPart::Digits), 1),
+                Token::number_e(),
+                Token::new(TokenKind::Number(NumberPart::ExponentPower), 1),
+            ]),
+            tokens(&p, "0x1.8p3")
+        );
+        assert_eq!(
+            Some(vec![
+                Token::new(TokenKind::Number(NumberPart::Prefix), 2),
+                Token::number_dot(),
+                Token::new(TokenKind::Number(NumberPart::Digits), 1),
+                Token::number_e(),
+                Token::number_e_sign(),
+                Token::new(TokenKind::Number(NumberPart::ExponentPower), 1),
+                Token::number_suffix(),
+            ]),
+            tokens(&p, "0X.Fp-2f")
+        );
+        assert_eq!(
+            Some(vec![
+                Token::new(TokenKind::Number(NumberPart::Prefix), 2),
+                Token::new(TokenKind::Number(NumberPart::Digits), 1),
+                Token::number_e(),
+                Token::new(TokenKind::Number(NumberPart::ExponentPower), 2),
+                Token::number_suffix(),
+            ]),
+            tokens(&p, "0x1P10d")
+        );
+        assert_eq!(
+            Some(vec![
+                Token::new(TokenKind::Number(NumberPart::Prefix), 2),
+                Token::new(TokenKind::Number(NumberPart::Digits), 10),
+                Token::number_dot(),
+                Token::number_e(),
+                Token::new(TokenKind::Number(NumberPart::ExponentPower), 3),
+            ]),
+            tokens(&p, "0x1_FFFF_FFF.p990")
         );
+        assert_eq!(None, tokens(&p, "0x.p1"));
+        assert_eq!(None, tokens(&p, "0x1.8"));
+        assert_eq!(Some(IllegalUnderscore), diagnostic(&p, "0x_1p1"));
+        assert_eq!(Some(MalformedFpLiteral), diagnostic(&p, "0x1p;"));
+    }
+
+    #[test]
+    fn hex_float_bounds() {
+        let p = HexFloatParser::new();
+        for code in &[
+            "0x1p127f",
+            "0x1.fffffep127f",
+            "0x1p-149f",
+            "0x0.0000_01p-125f",
+            "0x1p1023",
+            "0x1.fffffffffffffp1023",
+            "0x1p-1074",
+            "0x1.8p-1075",
+            "0x0p99999999999999999999",
+        ] {
+            assert!(p.parse(code, signed()).is_some(), "{}", code);
+            assert_eq!(None, diagnostic(&p, code), "{}", code);
+        }
+        for code in &[
+            "0x1p128f",
+            "0x1p1024",
+            "0x1p99999999999999999999",
+            "0x1.fffffffffffff8p1023",
+            "0x1.ffffffp127f",
+        ] {
+            assert_eq!(Some(FpNumberTooLarge), diagnostic(&p, code), "{}", code);
+        }
+        for code in &["0x1p-150f", "0x1p-1075", "0x1p-99999999999999999999"] {
+            assert_eq!(Some(FpNumberTooSmall), diagnostic(&p, code), "{}", code);
+        }
     }
 
     #[test]
     fn number_parser() {
         let p = NumberParser::new();
         assert_eq!(
-            Some(vec![Token::new("number", 1)]),
-            p.parse(&"0", ParserContext::new())
+            Some(vec![Token::new(TokenKind::Number(NumberPart::Digits), 1)]),
+            tokens(&p, "0")
         );
         assert_eq!(
             Some(vec![
                 Token::number_sign(),
-                Token::new("number.prefix", 2),
-                Token::new("number", 1),
+                Token::new(TokenKind::Number(NumberPart::Prefix), 2),
+                Token::new(TokenKind::Number(NumberPart::Digits), 1),
             ]),
-            p.parse(&"+0b1", ParserContext::new())
+            tokens(&p, "+0b1")
         );
         assert_eq!(
             Some(vec![
                 Token::number_sign(),
-                Token::whitespace(2),
-                Token::new("number.prefix", 1),
-                Token::new("number", 3),
+                Token::new(TokenKind::LineTerminator, 1),
+                Token::whitespace(1),
+                Token::new(TokenKind::Number(NumberPart::Prefix), 1),
+                Token::new(TokenKind::Number(NumberPart::Digits), 3),
             ]),
-            p.parse(&"-\n 0234", ParserContext::new())
+            tokens(&p, "-\n 0234")
+        );
+        assert_eq!(
+            Some(vec![Token::new(TokenKind::Number(NumberPart::Digits), 10)]),
+            tokens(&p, "2147483647")
         );
         assert_eq!(
-            Some(vec![Token::new("number", 10)]),
-            p.parse("2147483647", ParserContext::new())
+            Some(vec![Token::new(TokenKind::Number(NumberPart::Digits), 10)
+                .with_diagnostic(Diagnostic::new(
+                    IntNumberTooLarge,
+                    String::from("integer number too large: 2147483648"),
+                    10
+                ))]),
+            tokens(&p, "2147483648")
         );
-        assert_eq!(None, p.parse("2147483648", ParserContext::new()));
         assert_eq!(
-            Some(vec![Token::number_sign(), Token::new("number", 10)]),
-            p.parse("-2147483648", ParserContext::new())
+            Some(vec![
+                Token::new(TokenKind::Number(NumberPart::Digits), 2).with_diagnostic(
+                    Diagnostic::new(IllegalUnderscore, String::from("illegal underscore"), 4)
+                ),
+                Token::number_dot(),
+                Token::new(TokenKind::Number(NumberPart::Digits), 1),
+            ]),
+            tokens(&p, "1_.5")
+        );
+        assert_eq!(
+            Some(vec![
+                Token::number_sign(),
+                Token::new(TokenKind::Number(NumberPart::Digits), 10)
+            ]),
+            tokens(&p, "-2147483648")
+        );
+        assert_eq!(
+            Some(vec![
+                Token::new(TokenKind::Number(NumberPart::Prefix), 2),
+                Token::new(TokenKind::Number(NumberPart::Digits), 1),
+                Token::number_e(),
+                Token::new(TokenKind::Number(NumberPart::ExponentPower), 1),
+            ]),
+            tokens(&p, "0x1p3")
+        );
+    }
+
+    #[test]
+    fn number_errors() {
+        let p = NumberParser::new();
+        let error = |code| p.try_parse(code, ParserContext::new()).unwrap_err();
+        let e = error("x");
+        assert_eq!(ErrorCode::NoMatch, e.code);
+        assert_eq!(vec![TokenKind::Number(NumberPart::Digits)], e.expected);
+        assert_eq!("expected number", e.message);
+        let e = error("0x1_0000_0000;");
+        assert_eq!(ErrorCode::Malformed(IntNumberTooLarge), e.code);
+        assert_eq!(0..13, e.span);
+        assert_eq!(
+            Some(&Fix::new(
+                "Make it a long literal",
+                13..13,
+                String::from("L")
+            )),
+            e.fix.as_deref()
+        );
+        // A long would not fit it either.
+        assert_eq!(None, error("9223372036854775808").fix);
+        assert_eq!(None, error("9223372036854775808L").fix);
+        let e = p.try_parse("- 1_e3", signed()).unwrap_err();
+        assert_eq!(ErrorCode::Malformed(IllegalUnderscore), e.code);
+        assert_eq!(
+            Some(&Fix::new(
+                "Remove the underscores",
+                2..6,
+                String::from("1e3")
+            )),
+            e.fix.as_deref()
+        );
+        assert_eq!(None, error("0x.p1").fix);
+        assert!(p.try_parse("1_000", ParserContext::new()).is_ok());
+    }
+
+    fn value(code: &str, key: &str) -> Option<Value> {
+        NumberParser::new()
+            .parse(code, signed())
+            .unwrap()
+            .into_iter()
+            .find_map(|t| t.metadata.get(key).copied())
+    }
+
+    #[test]
+    fn number_values() {
+        assert_eq!(Some(Value::Int(32767)), value("0x7F_FF", "value"));
+        assert_eq!(Some(Value::Int(16)), value("0x7F_FF", "radix"));
+        assert_eq!(Some(Value::Int(-1)), value("0xFFFF_FFFF", "value"));
+        assert_eq!(Some(Value::Int(i32::MIN)), value("-2147483648", "value"));
+        assert_eq!(
+            Some(Value::Long(i64::MIN)),
+            value("- 9223372036854775808L", "value")
+        );
+        assert_eq!(
+            Some(Value::Long(-1)),
+            value(
+                "0b1111111111111111111111111111111111111111111111111111111111111111L",
+                "value"
+            )
+        );
+        assert_eq!(Some(Value::Int(8)), value("0_10", "radix"));
+        assert_eq!(Some(Value::Int(2)), value("0b101", "radix"));
+        assert_eq!(Some(Value::Int(5)), value("0b101", "value"));
+        assert_eq!(None, value("0x1_0000_0000", "value"));
+
+        assert_eq!(Some(Value::Double(1.5)), value("1.5", "value"));
+        assert_eq!(Some(Value::Int(10)), value("1.5", "radix"));
+        assert_eq!(Some(Value::Float(-0.25)), value("-.25f", "value"));
+        assert_eq!(Some(Value::Double(1e10)), value("1e10", "value"));
+        assert_eq!(Some(Value::Double(0.5)), value("0x1p-1", "value"));
+        assert_eq!(Some(Value::Float(3.0)), value("0x1.8p1f", "value"));
+        assert_eq!(Some(Value::Int(16)), value("0x1.8p1f", "radix"));
+        assert_eq!(
+            Some(Value::Double(f64::MIN_POSITIVE)),
+            value("0x1p-1022", "value")
+        );
+        assert_eq!(
+            Some(Value::Double(5e-324)),
+            value("0x0.0000000000001p-1022", "value")
+        );
+        // Rounding through a double first would round this down to 1.
+        assert_eq!(
+            Some(Value::Float(1.000_000_1)),
+            value("0x1.0000010000000001p0f", "value")
+        );
+        assert_eq!(Some(Value::Float(1.0)), value("0x1.000001p0f", "value"));
+        assert_eq!(
+            Some(Value::Float(f32::from_bits(1))),
+            value("0x1.0000000001p-150f", "value")
+        );
+    }
+
+    #[test]
+    fn exact_floats() {
+        let exact = |code| value(code, "exact") == Some(Value::Int(1));
+        assert!(exact("1.5"));
+        assert!(exact("0.0"));
+        assert!(exact("1_5e-1"));
+        assert!(exact("0.375f"));
+        assert!(exact("1e22"));
+        assert!(exact("9007199254740992d"));
+        assert!(!exact("4.9406564584124654e-324"));
+        assert!(exact("16777216f"));
+        assert!(!exact("0.1"));
+        assert!(!exact("1e23"));
+        assert!(!exact("9007199254740993d"));
+        assert!(!exact("16777217f"));
+        assert!(!exact("0.1f"));
+        assert!(exact("0x1.fffffffffffffp1023"));
+        assert!(exact("0x1.fffffep127f"));
+        assert!(!exact("0x1.fffffffp127f"));
+        assert!(!exact("0x1.00000000000001p0"));
+        assert!(exact("0x1p-1074"));
+        assert!(!exact("0x1.8p-1074"));
+        assert_eq!(None, value("123", "exact"));
+    }
+
+    #[test]
+    fn illegal_digits() {
+        let p = NumberParser::new();
+        let len = |code| p.parse(code, signed()).unwrap().iter().map(|t| t.len).sum();
+        for code in ["09", "0_9", "0b2"].iter().copied() {
+            assert_eq!(code.len(), len(code), "{}", code);
+        }
+        assert_eq!(Some(IllegalOctalDigit), diagnostic(&p, "09"));
+        assert_eq!(Some(IllegalOctalDigit), diagnostic(&p, "0_9"));
+        assert_eq!(Some(IllegalBinaryDigit), diagnostic(&p, "0b2"));
+        assert_eq!(None, diagnostic(&p, "09.5"));
+        assert_eq!(None, diagnostic(&p, "09e1"));
+    }
+
+    #[test]
+    fn unsigned_numbers() {
+        let p = NumberParser::new();
+        let c = ParserContext::new();
+        assert_eq!(None, p.parse("-1", c));
+        assert_eq!(None, p.parse("+ 1.5", c));
+        assert_eq!(Some(IntNumberTooLarge), diagnostic(&p, "2147483648"));
+        let lexer = Lexer::new();
+        let names = |code| {
+            lexer
+                .lex(code, c)
+                .into_iter()
+                .filter(|t| t.kind != TokenKind::Whitespace)
+                .map(|t| (t.name(), t.diagnostic.map(|d| d.kind)))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            vec![
+                ("identifier", None),
+                ("operator.arithmetic", None),
+                ("number", None)
+            ],
+            names("a-1")
+        );
+        // Only the operand of a unary minus may be `MIN_VALUE`'s magnitude.
+        assert_eq!(
+            vec![
+                ("operator.arithmetic", None),
+                ("number", None),
+                ("number.suffix", None)
+            ],
+            names("- 9223372036854775808L")
+        );
+        assert_eq!(
+            vec![
+                ("separator.paren", None),
+                ("operator.arithmetic", None),
+                ("number", None),
+                ("separator.paren", None)
+            ],
+            names("(-2147483648)")
+        );
+        assert_eq!(
+            vec![
+                ("identifier", None),
+                ("operator.arithmetic", None),
+                ("number", Some(IntNumberTooLarge))
+            ],
+            names("a - 2147483648")
+        );
+        assert_eq!(
+            vec![
+                ("separator.paren", None),
+                ("operator.arithmetic", None),
+                ("number", Some(IntNumberTooLarge))
+            ],
+            names("(-2147483649")
         );
+        let min = lexer.lex("-2147483648", c).remove(1);
+        assert_eq!(Some(&Value::Int(i32::MIN)), min.metadata.get("value"));
     }
 }
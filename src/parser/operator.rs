@@ -0,0 +1,325 @@
+use lazy_static::lazy_static;
+
+use crate::parser::{
+    identifier::{IDENTIFIER_PART, IDENTIFIER_START},
+    pattern::Pattern,
+    OperatorKind, Parser, ParserContext, SeparatorKind, Token, TokenKind,
+};
+
+/// Operators by category, see JLS 3.12.
+const OPERATORS: &[(OperatorKind, &[&str])] = &[
+    (
+        OperatorKind::Arithmetic,
+        &["+", "-", "*", "/", "%", "++", "--"],
+    ),
+    (OperatorKind::Bitwise, &["&", "|", "^", "~"]),
+    (OperatorKind::Shift, &["<<", ">>", ">>>"]),
+    (
+        OperatorKind::Comparison,
+        &["==", "!=", "<", ">", "<=", ">="],
+    ),
+    (OperatorKind::Logical, &["&&", "||", "!"]),
+    (
+        OperatorKind::Assignment,
+        &[
+            "=", "+=", "-=", "*=", "/=", "%=", "&=", "|=", "^=", "<<=", ">>=", ">>>=",
+        ],
+    ),
+    (OperatorKind::Conditional, &["?", ":"]),
+    (OperatorKind::Arrow, &["->"]),
+];
+
+/// Separators by category, see JLS 3.11.
+const SEPARATORS: &[(SeparatorKind, &[&str])] = &[
+    (SeparatorKind::Paren, &["(", ")"]),
+    (SeparatorKind::Brace, &["{", "}"]),
+    (SeparatorKind::Bracket, &["[", "]"]),
+    (SeparatorKind::Semicolon, &[";"]),
+    (SeparatorKind::Comma, &[","]),
+    (SeparatorKind::Dot, &["."]),
+    (SeparatorKind::Ellipsis, &["..."]),
+    (SeparatorKind::At, &["@"]),
+    (SeparatorKind::MethodReference, &["::"]),
+];
+
+lazy_static! {
+    /// What may appear between the angle brackets of type arguments, other than nested
+    /// brackets: names, wildcards, bounds, array dimensions and annotations.
+    static ref TYPE_ARGUMENT_PART_REGEX: Pattern = Pattern::new(&format!(
+        r"^(?:[ \t\x0C\r\n]+|[{}][{}]*|[.,?\[\]@]|&[^&])",
+        IDENTIFIER_START, IDENTIFIER_PART
+    ));
+    static ref NAME_START_REGEX: Pattern =
+        Pattern::new(&format!("^[{}]", IDENTIFIER_START));
+    /// What may follow the closing `>` of type arguments, other than another `>` and
+    /// an identifier: calls, casts, method references, qualified names, array types,
+    /// lists of types, bounds and bodies.
+    static ref TYPE_ARGUMENTS_END_REGEX: Pattern = Pattern::new(r"^(?:[()\[.,;{&]|::|$)");
+}
+
+/// Whether the `<` that `code` starts with opens type arguments or type parameters,
+/// rather than being a less-than comparison. This looks ahead for the matching `>`
+/// with only what may appear in a type in between, and checks that what follows it
+/// may follow a type.
+fn is_type_arguments(code: &str, context: ParserContext) -> bool {
+    let mut depth = 0;
+    let mut offset = 0;
+    while offset < code.len() {
+        let rest = &code[offset..];
+        if rest.starts_with('<') {
+            depth += 1;
+            offset += 1;
+        } else if rest.starts_with('>') {
+            depth -= 1;
+            offset += 1;
+            if depth == 0 {
+                return can_end_type_arguments(&code[offset..], context);
+            }
+        } else if let Some(part) = TYPE_ARGUMENT_PART_REGEX.find(rest) {
+            // `&[^&]` only checks the character after a bound's `&`.
+            offset += if rest.starts_with('&') { 1 } else { part.end() };
+        } else {
+            return false;
+        }
+    }
+    false
+}
+
+/// Whether `code`, which follows the `>` closing type arguments, may follow them.
+/// A `>` right after closes an enclosing list, so it is a shift outside of one, as
+/// in `i < n >> 1`. An identifier may only follow a type named by convention, which
+/// keeps `f(a < b, c > d)` two comparisons.
+fn can_end_type_arguments(code: &str, context: ParserContext) -> bool {
+    if code.starts_with('=') {
+        return false;
+    }
+    let code = code.trim_start_matches([' ', '\t', '\x0C', '\r', '\n']);
+    if code.starts_with('>') {
+        context.generic_depth() > 0
+    } else if NAME_START_REGEX.is_match(code) {
+        !context.is_after_lower_case_name()
+    } else {
+        TYPE_ARGUMENTS_END_REGEX.is_match(code)
+    }
+}
+
+/// Tokenizes operators and separators, taking the longest one the code starts with
+/// so that `>>>=` is a single assignment rather than a shift followed by a comparison.
+///
+/// Angle brackets around type arguments are `separator.angle` tokens instead. Inside
+/// them, every `>` is a token of its own, so the `>>` ending `List<List<T>>` closes
+/// both lists.
+pub struct OperatorParser;
+
+impl OperatorParser {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for OperatorParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Parser for OperatorParser {
+    fn parse(&self, code: &str, context: ParserContext) -> Option<Vec<Token>> {
+        let angle = match code.chars().next() {
+            Some('<') => is_type_arguments(code, context),
+            Some('>') => context.generic_depth() > 0,
+            _ => false,
+        };
+        if angle {
+            return Some(vec![Token::new(
+                TokenKind::Separator(SeparatorKind::Angle),
+                1,
+            )]);
+        }
+        let operators = OPERATORS
+            .iter()
+            .flat_map(|&(kind, ops)| ops.iter().map(move |&op| (TokenKind::Operator(kind), op)));
+        let separators = SEPARATORS
+            .iter()
+            .flat_map(|&(kind, ops)| ops.iter().map(move |&op| (TokenKind::Separator(kind), op)));
+        operators
+            .chain(separators)
+            .filter(|(_, op)| code.starts_with(op))
+            .max_by_key(|(_, op)| op.len())
+            .map(|(kind, op)| vec![Token::new(kind, op.len())])
+    }
+
+    fn patterns(&self) -> Vec<&'static Pattern> {
+        vec![
+            &TYPE_ARGUMENT_PART_REGEX,
+            &NAME_START_REGEX,
+            &TYPE_ARGUMENTS_END_REGEX,
+        ]
+    }
+
+    fn expected(&self) -> Vec<TokenKind> {
+        let operators = OPERATORS.iter().map(|&(kind, _)| TokenKind::Operator(kind));
+        let separators = SEPARATORS
+            .iter()
+            .map(|&(kind, _)| TokenKind::Separator(kind));
+        operators.chain(separators).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{lexer::Lexer, Parser, ParserContext, Token};
+    use super::*;
+
+    fn operator(code: &str) -> Option<(&'static str, usize)> {
+        OperatorParser::new()
+            .parse(code, ParserContext::new())
+            .map(|t| (t[0].name(), t[0].len))
+    }
+
+    #[test]
+    fn operator_parser() {
+        let p = OperatorParser::new();
+        assert_eq!(
+            Some(vec![Token::new(
+                TokenKind::Operator(OperatorKind::Assignment),
+                2
+            )]),
+            p.parse("+= 1", ParserContext::new())
+        );
+        assert_eq!(
+            Some(vec![Token::new(
+                TokenKind::Separator(SeparatorKind::Brace),
+                1
+            )]),
+            p.parse("{}", ParserContext::new())
+        );
+        assert_eq!(None, p.parse("a", ParserContext::new()));
+        assert_eq!(None, p.parse("#", ParserContext::new()));
+    }
+
+    #[test]
+    fn maximal_munch() {
+        assert_eq!(Some(("operator.assignment", 4)), operator(">>>=1"));
+        assert_eq!(Some(("operator.shift", 3)), operator(">>>1"));
+        assert_eq!(Some(("operator.shift", 2)), operator(">>1"));
+        assert_eq!(Some(("operator.comparison", 2)), operator(">=1"));
+        assert_eq!(Some(("operator.comparison", 1)), operator("> >"));
+        assert_eq!(Some(("operator.arithmetic", 2)), operator("+++"));
+        assert_eq!(Some(("operator.arrow", 2)), operator("->"));
+        assert_eq!(Some(("operator.arithmetic", 1)), operator("- >"));
+        assert_eq!(Some(("operator.logical", 2)), operator("&&="));
+        assert_eq!(Some(("operator.logical", 1)), operator("!!"));
+        assert_eq!(Some(("operator.conditional", 1)), operator(": :"));
+        assert_eq!(Some(("separator.method_reference", 2)), operator(":::"));
+        assert_eq!(Some(("separator.ellipsis", 3)), operator("...."));
+        assert_eq!(Some(("separator.dot", 1)), operator("..x"));
+        assert_eq!(Some(("separator.at", 1)), operator("@Override"));
+        assert_eq!(Some(("separator.bracket", 1)), operator("]"));
+    }
+
+    #[test]
+    fn generics() {
+        let l = Lexer::new();
+        let angles = |code| {
+            l.lex(code, ParserContext::new())
+                .into_iter()
+                .filter(|t| matches!(t.kind, TokenKind::Operator(_) | TokenKind::Separator(_)))
+                .map(|t| (t.name(), t.len))
+                .collect::<Vec<_>>()
+        };
+        let angle = ("separator.angle", 1);
+        let comma = ("separator.comma", 1);
+        let semicolon = ("separator.semicolon", 1);
+        let assignment = ("operator.assignment", 1);
+        assert_eq!(
+            vec![angle, comma, angle, angle, angle, semicolon],
+            angles("Map<String, List<Integer>> m;")
+        );
+        assert_eq!(
+            vec![angle, angle, angle, angle, angle, angle, assignment],
+            angles("A<B<C<D>>> x =")
+        );
+        assert_eq!(
+            vec![
+                angle,
+                ("operator.conditional", 1),
+                angle,
+                angle,
+                ("operator.bitwise", 1),
+                angle
+            ],
+            angles("Class<? extends Comparable<T> & Serializable>")
+        );
+        assert_eq!(
+            vec![("operator.shift", 2), ("operator.shift", 3), semicolon],
+            angles("a >> b >>> c;")
+        );
+        assert_eq!(
+            vec![
+                ("operator.comparison", 1),
+                ("operator.logical", 2),
+                ("operator.comparison", 1)
+            ],
+            angles("a < b && c > d")
+        );
+        assert_eq!(
+            vec![
+                ("separator.paren", 1),
+                ("operator.comparison", 1),
+                semicolon,
+                ("operator.arithmetic", 2),
+                ("separator.paren", 1)
+            ],
+            angles("(i < 10; i++)")
+        );
+        // A misread `<` does not leak past the end of the statement.
+        assert_eq!(
+            vec![angle, angle, semicolon, ("operator.shift", 2)],
+            angles("a < b > ; x >> 1")
+        );
+        let shift = |len| ("operator.shift", len);
+        let comparison = ("operator.comparison", 1);
+        assert_eq!(vec![comparison, shift(2)], angles("i < n >> 1"));
+        assert_eq!(
+            vec![assignment, comparison, shift(3), semicolon],
+            angles("x = i < n >>> 2;")
+        );
+        assert_eq!(
+            vec![
+                ("separator.paren", 1),
+                comparison,
+                shift(2),
+                ("separator.paren", 1)
+            ],
+            angles("if (a < b >> 1)")
+        );
+        assert_eq!(
+            vec![
+                ("separator.paren", 1),
+                comparison,
+                comma,
+                comparison,
+                ("separator.paren", 1)
+            ],
+            angles("f(a < b, c > d)")
+        );
+        assert_eq!(
+            vec![
+                ("separator.paren", 1),
+                angle,
+                comma,
+                angle,
+                comma,
+                angle,
+                angle,
+                ("separator.paren", 1)
+            ],
+            angles("void f(Map<A, B> m, List<C> c)")
+        );
+        assert_eq!(
+            vec![angle, angle, ("separator.brace", 1), ("separator.brace", 1)],
+            angles("class A extends B<C> {}")
+        );
+    }
+}
@@ -0,0 +1,118 @@
+use regex::{Captures, Match, Regex};
+
+/// A regex the parsers match code against, or why it does not compile.
+///
+/// The patterns are built once from constant strings, so they should always compile.
+/// Rather than panicking when one does not, it matches nothing, and parsers list their
+/// patterns in `Parser::patterns` for `try_parse` to report the error.
+pub struct Pattern(Result<Regex, regex::Error>);
+
+impl Pattern {
+    pub fn new(re: &str) -> Self {
+        Self(Regex::new(re))
+    }
+
+    /// Why the pattern does not compile, if it does not.
+    pub fn error(&self) -> Option<&regex::Error> {
+        self.0.as_ref().err()
+    }
+
+    pub fn is_match(&self, text: &str) -> bool {
+        self.0.as_ref().is_ok_and(|r| r.is_match(text))
+    }
+
+    pub fn find<'t>(&self, text: &'t str) -> Option<Match<'t>> {
+        self.0.as_ref().ok()?.find(text)
+    }
+
+    pub fn captures<'t>(&self, text: &'t str) -> Option<Captures<'t>> {
+        self.0.as_ref().ok()?.captures(text)
+    }
+
+    pub fn find_iter<'r, 't>(&'r self, text: &'t str) -> impl Iterator<Item = Match<'t>> + 'r
+    where
+        't: 'r,
+    {
+        self.0.iter().flat_map(move |r| r.find_iter(text))
+    }
+
+    /// The parts of `text` between matches, or all of it when the pattern does not compile.
+    pub fn split<'t>(&self, text: &'t str) -> Vec<&'t str> {
+        match &self.0 {
+            Ok(r) => r.split(text).collect(),
+            Err(_) => vec![text],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lazy_static::lazy_static;
+
+    use super::*;
+    use crate::parser::{
+        annotation::AnnotationParser, character::CharacterParser, comment::CommentParser,
+        identifier::IdentifierParser, keyword::KeywordParser, number::NumberParser,
+        operator::OperatorParser, string::StringParser, text_block::TextBlockParser, ErrorCode,
+        Parser, ParserContext, Token,
+    };
+
+    lazy_static! {
+        static ref BROKEN_REGEX: Pattern = Pattern::new(r"(");
+    }
+
+    struct BrokenParser;
+
+    impl Parser for BrokenParser {
+        fn parse(&self, code: &str, _: ParserContext) -> Option<Vec<Token>> {
+            BROKEN_REGEX
+                .find(code)
+                .map(|m| vec![Token::unknown(m.end())])
+        }
+
+        fn patterns(&self) -> Vec<&'static Pattern> {
+            vec![&BROKEN_REGEX]
+        }
+    }
+
+    #[test]
+    fn pattern() {
+        let p = Pattern::new(r"\d+");
+        assert!(p.error().is_none());
+        assert_eq!(Some("12"), p.find("a12b3").map(|m| m.as_str()));
+        assert_eq!(
+            vec!["12", "3"],
+            p.find_iter("a12b3").map(|m| m.as_str()).collect::<Vec<_>>()
+        );
+        assert_eq!(vec!["a", "b", ""], p.split("a12b3"));
+        let p = Pattern::new(r"(");
+        assert!(p.error().is_some());
+        assert!(!p.is_match("("));
+        assert_eq!(None, p.find("("));
+        assert_eq!(0, p.find_iter("((").count());
+        assert_eq!(vec!["a(b"], p.split("a(b"));
+    }
+
+    #[test]
+    fn broken_patterns() {
+        let error = BrokenParser
+            .try_parse("(", ParserContext::new())
+            .unwrap_err();
+        assert_eq!(ErrorCode::Internal, error.code);
+        let parsers: Vec<Box<dyn Parser>> = vec![
+            Box::new(AnnotationParser::new()),
+            Box::new(CharacterParser::new()),
+            Box::new(CommentParser::new()),
+            Box::new(IdentifierParser::new()),
+            Box::new(KeywordParser::new()),
+            Box::new(NumberParser::new()),
+            Box::new(OperatorParser::new()),
+            Box::new(StringParser::new()),
+            Box::new(TextBlockParser::new()),
+        ];
+        for parser in &parsers {
+            assert!(!parser.patterns().is_empty());
+            assert!(parser.patterns().iter().all(|p| p.error().is_none()));
+        }
+    }
+}
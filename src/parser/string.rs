@@ -0,0 +1,197 @@
+use lazy_static::lazy_static;
+
+use crate::parser::{
+    pattern::Pattern, Feature, Parser, ParserContext, QuotedPart, Token, TokenKind,
+};
+
+lazy_static! {
+    pub(crate) static ref ESCAPE_REGEX: Pattern =
+        Pattern::new(r#"^\\(?:[btnfr"'\\]|[0-3][0-7]?[0-7]?|[4-7][0-7]?|s|\r\n|\r|\n)"#);
+    static ref CONTENT_REGEX: Pattern = Pattern::new(r#"^[^"\\\r\n]+"#);
+}
+
+/// Returns the length of the escape sequence `code` starts with, or `None` if it is
+/// not a valid one. `\s` and escaped line terminators came with text blocks, which
+/// are the only literals line terminators may be escaped in.
+pub(crate) fn escape_len(code: &str, context: ParserContext) -> Option<usize> {
+    let escape = ESCAPE_REGEX.find(code)?.as_str();
+    let text_block_escape = escape == r"\s" || escape.ends_with('\n') || escape.ends_with('\r');
+    if text_block_escape && !context.supports(Feature::TextBlocks) {
+        return None;
+    }
+    Some(escape.len())
+}
+
+/// Tokenizes the escape sequence `code` starts with, marking the backslash and the
+/// character following it as `invalid` if it is not a valid one.
+pub(crate) fn escape(code: &str, kind: TokenKind, context: ParserContext) -> Token {
+    match escape_len(code, context) {
+        Some(len) => Token::new(kind, len),
+        None => match code[1..].chars().next() {
+            Some(c) if c != '\r' && c != '\n' => Token::invalid(1 + c.len_utf8()),
+            _ => Token::invalid(1),
+        },
+    }
+}
+
+/// Tokenizes the escape sequence `code` starts with in a string or character literal,
+/// which may not span lines, so an escaped line terminator leaves an `invalid` backslash.
+pub(crate) fn literal_escape(code: &str, kind: TokenKind, context: ParserContext) -> Token {
+    let token = escape(code, kind, context);
+    if code[..token.len].ends_with(['\r', '\n']) {
+        Token::invalid(1)
+    } else {
+        token
+    }
+}
+
+pub struct StringParser;
+
+impl StringParser {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for StringParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Parser for StringParser {
+    fn parse(&self, code: &str, context: ParserContext) -> Option<Vec<Token>> {
+        if !code.starts_with('"') {
+            return None;
+        }
+        let mut tokens = vec![Token::new(TokenKind::String(QuotedPart::Quote), 1)];
+        let mut offset = 1;
+        loop {
+            let rest = &code[offset..];
+            let token = match rest.chars().next() {
+                Some('"') => {
+                    tokens.push(Token::new(TokenKind::String(QuotedPart::Quote), 1));
+                    return Some(tokens);
+                }
+                Some('\\') => literal_escape(rest, TokenKind::String(QuotedPart::Escape), context),
+                Some('\r') | Some('\n') | None => {
+                    // Unterminated, the line terminator is left for the next token.
+                    tokens[0] = Token::invalid(1);
+                    return Some(tokens);
+                }
+                Some(_) => Token::new(
+                    TokenKind::String(QuotedPart::Content),
+                    CONTENT_REGEX.find(rest)?.end(),
+                ),
+            };
+            offset += token.len;
+            tokens.push(token);
+        }
+    }
+
+    fn patterns(&self) -> Vec<&'static Pattern> {
+        vec![&ESCAPE_REGEX, &CONTENT_REGEX]
+    }
+
+    fn expected(&self) -> Vec<TokenKind> {
+        vec![TokenKind::String(QuotedPart::Quote)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{Parser, ParserContext, Token};
+    use super::*;
+
+    #[test]
+    fn string_parser() {
+        let p = StringParser::new();
+        assert_eq!(
+            Some(vec![
+                Token::new(TokenKind::String(QuotedPart::Quote), 1),
+                Token::new(TokenKind::String(QuotedPart::Content), 1),
+                Token::new(TokenKind::String(QuotedPart::Escape), 2),
+                Token::new(TokenKind::String(QuotedPart::Content), 1),
+                Token::new(TokenKind::String(QuotedPart::Quote), 1),
+            ]),
+            p.parse(r#""a\"b" + c"#, ParserContext::new())
+        );
+        assert_eq!(
+            Some(vec![
+                Token::new(TokenKind::String(QuotedPart::Quote), 1),
+                Token::new(TokenKind::String(QuotedPart::Quote), 1),
+            ]),
+            p.parse(r#""""#, ParserContext::new())
+        );
+        assert_eq!(None, p.parse("abc", ParserContext::new()));
+    }
+
+    #[test]
+    fn string_escapes() {
+        let p = StringParser::new();
+        assert_eq!(
+            Some(vec![
+                Token::new(TokenKind::String(QuotedPart::Quote), 1),
+                Token::new(TokenKind::String(QuotedPart::Escape), 2),
+                Token::new(TokenKind::String(QuotedPart::Escape), 2),
+                Token::new(TokenKind::String(QuotedPart::Escape), 2),
+                Token::new(TokenKind::String(QuotedPart::Escape), 4),
+                Token::new(TokenKind::String(QuotedPart::Escape), 3),
+                Token::new(TokenKind::String(QuotedPart::Escape), 2),
+                Token::new(TokenKind::String(QuotedPart::Content), 1),
+                Token::new(TokenKind::String(QuotedPart::Escape), 2),
+                Token::new(TokenKind::String(QuotedPart::Escape), 2),
+                Token::new(TokenKind::String(QuotedPart::Escape), 2),
+                Token::new(TokenKind::String(QuotedPart::Escape), 2),
+                Token::new(TokenKind::String(QuotedPart::Quote), 1),
+            ]),
+            p.parse(r#""\n\t\\\377\47\09\"\0\s\'"x"#, ParserContext::new())
+        );
+    }
+
+    #[test]
+    fn invalid_strings() {
+        let p = StringParser::new();
+        assert_eq!(
+            Some(vec![
+                Token::new(TokenKind::String(QuotedPart::Quote), 1),
+                Token::invalid(2),
+                Token::invalid(3),
+                Token::new(TokenKind::String(QuotedPart::Quote), 1),
+            ]),
+            p.parse(r#""\q\ж""#, ParserContext::new())
+        );
+        assert_eq!(
+            Some(vec![
+                Token::new(TokenKind::String(QuotedPart::Quote), 1),
+                Token::invalid(2),
+                Token::new(TokenKind::String(QuotedPart::Quote), 1),
+            ]),
+            p.parse(r#""\s""#, ParserContext::new().with_release(8))
+        );
+        assert_eq!(
+            Some(vec![
+                Token::invalid(1),
+                Token::new(TokenKind::String(QuotedPart::Content), 3)
+            ]),
+            p.parse("\"abc\n\"", ParserContext::new())
+        );
+        assert_eq!(
+            Some(vec![Token::invalid(1), Token::invalid(1)]),
+            p.parse("\"\\\n", ParserContext::new().with_release(8))
+        );
+        // Only text blocks may escape a line terminator, strings end at it.
+        assert_eq!(
+            Some(vec![
+                Token::invalid(1),
+                Token::new(TokenKind::String(QuotedPart::Content), 1),
+                Token::invalid(1),
+            ]),
+            p.parse("\"a\\\r\nb\"", ParserContext::new())
+        );
+        assert_eq!(
+            Some(vec![Token::invalid(1)]),
+            p.parse("\"", ParserContext::new())
+        );
+    }
+}
@@ -0,0 +1,229 @@
+use lazy_static::lazy_static;
+
+use crate::hashmap;
+use crate::parser::{
+    pattern::Pattern,
+    string::{escape, ESCAPE_REGEX},
+    whitespace::line_terminator,
+    Feature, Parser, ParserContext, QuotedPart, Token, TokenKind, Value,
+};
+
+lazy_static! {
+    static ref OPENING_REGEX: Pattern = Pattern::new(r#"^"""(?:([ \t\x0C]*)(\r\n|\r|\n))?"#);
+    static ref LINE_TERMINATOR_REGEX: Pattern = Pattern::new(r"\r\n|\r|\n");
+}
+
+/// Computes the incidental indentation `String::stripIndent` removes from `content`,
+/// the text between the opening line terminator and the closing delimiter. The last
+/// line counts even when blank if it holds the closing delimiter.
+fn incidental_indent(content: &str, closed: bool) -> usize {
+    let lines = LINE_TERMINATOR_REGEX.split(content);
+    lines
+        .iter()
+        .enumerate()
+        .filter(|(i, line)| {
+            (closed && *i == lines.len() - 1) || !line.chars().all(char::is_whitespace)
+        })
+        .map(|(_, line)| line.chars().take_while(|c| c.is_whitespace()).count())
+        .min()
+        .unwrap_or(0)
+}
+
+pub struct TextBlockParser;
+
+impl TextBlockParser {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for TextBlockParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Parser for TextBlockParser {
+    fn parse(&self, code: &str, context: ParserContext) -> Option<Vec<Token>> {
+        if !context.supports(Feature::TextBlocks) {
+            return None;
+        }
+        let opening = OPENING_REGEX.captures(code)?;
+        let mut tokens = vec![];
+        let terminated = opening.get(2).is_some();
+        if terminated {
+            // The opening line is tokenized like any other white space ending a line.
+            let spaces = opening[1].len();
+            if spaces > 0 {
+                tokens.push(Token::with_meta(
+                    TokenKind::Whitespace,
+                    spaces,
+                    hashmap!(String::from("trailing"), Value::Int(1)),
+                ));
+            }
+            tokens.extend(line_terminator(&opening[2]));
+        }
+        let mut offset = opening[0].len();
+        let content_start = offset;
+        let mut content_len = 0;
+        let closed = loop {
+            let rest = &code[offset..];
+            let token = if rest.starts_with(r#"""""#) {
+                Token::new(TokenKind::String(QuotedPart::Quote), 3)
+            } else if rest.starts_with('\\') {
+                escape(rest, TokenKind::String(QuotedPart::Escape), context)
+            } else if let Some(c) = rest.chars().next() {
+                content_len += c.len_utf8();
+                offset += c.len_utf8();
+                continue;
+            } else {
+                break false;
+            };
+            if content_len > 0 {
+                tokens.push(Token::new(
+                    TokenKind::String(QuotedPart::Content),
+                    content_len,
+                ));
+                content_len = 0;
+            }
+            offset += token.len;
+            let closing = token.kind == TokenKind::String(QuotedPart::Quote);
+            tokens.push(token);
+            if closing {
+                break true;
+            }
+        };
+        if content_len > 0 {
+            tokens.push(Token::new(
+                TokenKind::String(QuotedPart::Content),
+                content_len,
+            ));
+        }
+        let content_end = if closed { offset - 3 } else { offset };
+        let indent =
+            Value::Int(incidental_indent(&code[content_start..content_end], closed) as i32);
+        let opening = if terminated && closed {
+            Token::with_meta(
+                TokenKind::String(QuotedPart::Quote),
+                3,
+                hashmap!(String::from("indent"), indent),
+            )
+        } else {
+            // A missing line terminator after the opening delimiter or a missing closing
+            // delimiter makes the whole text block invalid.
+            Token::with_meta(
+                TokenKind::Invalid,
+                3,
+                hashmap!(String::from("indent"), indent),
+            )
+        };
+        tokens.insert(0, opening);
+        Some(tokens)
+    }
+
+    fn patterns(&self) -> Vec<&'static Pattern> {
+        vec![&OPENING_REGEX, &LINE_TERMINATOR_REGEX, &ESCAPE_REGEX]
+    }
+
+    fn expected(&self) -> Vec<TokenKind> {
+        vec![TokenKind::String(QuotedPart::Quote)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{Parser, ParserContext, Token};
+    use super::*;
+
+    fn opening(indent: i32) -> Token {
+        Token::with_meta(
+            TokenKind::String(QuotedPart::Quote),
+            3,
+            hashmap!(String::from("indent"), Value::Int(indent)),
+        )
+    }
+
+    fn terminator(flag: &str, len: usize) -> Token {
+        Token::with_meta(
+            TokenKind::LineTerminator,
+            len,
+            hashmap!(String::from(flag), Value::Int(1)),
+        )
+    }
+
+    #[test]
+    fn text_block_parser() {
+        let p = TextBlockParser::new();
+        let code = "\"\"\"  \n    <p>\n      \"hi\" \\\"\"\"\n\n    </p>\\\n    \"\"\";";
+        assert_eq!(
+            Some(vec![
+                opening(4),
+                Token::with_meta(
+                    TokenKind::Whitespace,
+                    2,
+                    hashmap!(String::from("trailing"), Value::Int(1))
+                ),
+                terminator("lf", 1),
+                Token::new(TokenKind::String(QuotedPart::Content), 19),
+                Token::new(TokenKind::String(QuotedPart::Escape), 2),
+                Token::new(TokenKind::String(QuotedPart::Content), 12),
+                Token::new(TokenKind::String(QuotedPart::Escape), 2),
+                Token::new(TokenKind::String(QuotedPart::Content), 4),
+                Token::new(TokenKind::String(QuotedPart::Quote), 3),
+            ]),
+            p.parse(code, ParserContext::new())
+        );
+        assert_eq!(
+            Some(vec![
+                opening(2),
+                terminator("crlf", 2),
+                Token::new(TokenKind::String(QuotedPart::Content), 10),
+                Token::new(TokenKind::String(QuotedPart::Quote), 3),
+            ]),
+            p.parse("\"\"\"\r\n    a\r\n  b\"\"\"", ParserContext::new())
+        );
+        assert_eq!(
+            Some(vec![
+                opening(0),
+                terminator("lf", 1),
+                Token::new(TokenKind::String(QuotedPart::Content), 4),
+                Token::new(TokenKind::String(QuotedPart::Quote), 3),
+            ]),
+            p.parse("\"\"\"\n  a\n\"\"\"", ParserContext::new())
+        );
+        assert_eq!(
+            None,
+            p.parse("\"\"\"\na\"\"\"", ParserContext::new().with_release(14))
+        );
+        assert_eq!(None, p.parse("\"a\"", ParserContext::new()));
+    }
+
+    #[test]
+    fn invalid_text_blocks() {
+        let p = TextBlockParser::new();
+        assert_eq!(
+            Some(vec![
+                Token::with_meta(
+                    TokenKind::Invalid,
+                    3,
+                    hashmap!(String::from("indent"), Value::Int(1))
+                ),
+                Token::new(TokenKind::String(QuotedPart::Content), 2),
+                Token::new(TokenKind::String(QuotedPart::Quote), 3),
+            ]),
+            p.parse("\"\"\" a\"\"\"", ParserContext::new())
+        );
+        assert_eq!(
+            Some(vec![
+                Token::with_meta(
+                    TokenKind::Invalid,
+                    3,
+                    hashmap!(String::from("indent"), Value::Int(2))
+                ),
+                terminator("lf", 1),
+                Token::new(TokenKind::String(QuotedPart::Content), 9),
+            ]),
+            p.parse("\"\"\"\n  a\n\n   b", ParserContext::new())
+        );
+    }
+}
@@ -0,0 +1,140 @@
+/// A Unicode escape that was translated, and where it is in both texts.
+struct Escape {
+    /// Byte offset of the translated character.
+    start: usize,
+    /// Byte offset right after the translated character.
+    end: usize,
+    /// Byte offset right after the escape in the raw source.
+    raw_end: usize,
+}
+
+/// Source code with its `\uXXXX` escapes translated to the characters they denote,
+/// which JLS 3.3 requires to happen before the code is split into tokens.
+///
+/// Offsets into the translated text can be mapped back to the raw source, so tokens
+/// found in the translated text can still be located in what the user wrote. Malformed
+/// escapes are left untranslated, and a lone surrogate, which a `String` cannot hold,
+/// becomes U+FFFD.
+pub struct Translation {
+    text: String,
+    escapes: Vec<Escape>,
+}
+
+/// Reads the escape `code` starts with, returning the code unit it denotes and its length.
+fn unicode_escape(code: &str) -> Option<(u32, usize)> {
+    if !code.starts_with('\\') {
+        return None;
+    }
+    let us = code[1..].bytes().take_while(|&b| b == b'u').count();
+    if us == 0 {
+        return None;
+    }
+    let digits = code.get(1 + us..1 + us + 4)?;
+    if !digits.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    Some((u32::from_str_radix(digits, 16).ok()?, 1 + us + 4))
+}
+
+impl Translation {
+    pub fn new(code: &str) -> Self {
+        let mut text = String::new();
+        let mut escapes = vec![];
+        let mut offset = 0;
+        // Number of backslashes right before `offset`, an escape needs an even number.
+        let mut backslashes = 0;
+        while let Some(c) = code[offset..].chars().next() {
+            let rest = &code[offset..];
+            let escape = match unicode_escape(rest) {
+                Some((unit, len)) if backslashes % 2 == 0 => match unit {
+                    0xD800..=0xDBFF => unicode_escape(&rest[len..])
+                        .filter(|(low, _)| (0xDC00..=0xDFFF).contains(low))
+                        .map(|(low, low_len)| {
+                            let c = 0x10000 + ((unit - 0xD800) << 10) + (low - 0xDC00);
+                            (char::from_u32(c).unwrap(), len + low_len)
+                        })
+                        .or(Some((char::REPLACEMENT_CHARACTER, len))),
+                    _ => Some((
+                        char::from_u32(unit).unwrap_or(char::REPLACEMENT_CHARACTER),
+                        len,
+                    )),
+                },
+                _ => None,
+            };
+            match escape {
+                Some((c, len)) => {
+                    let start = text.len();
+                    text.push(c);
+                    offset += len;
+                    escapes.push(Escape {
+                        start,
+                        end: text.len(),
+                        raw_end: offset,
+                    });
+                    backslashes = 0;
+                }
+                None => {
+                    text.push(c);
+                    offset += c.len_utf8();
+                    backslashes = if c == '\\' { backslashes + 1 } else { 0 };
+                }
+            }
+        }
+        Self { text, escapes }
+    }
+
+    /// The code with its escapes translated.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Whether any escape was translated, so that offsets differ between the texts.
+    pub fn is_translated(&self) -> bool {
+        !self.escapes.is_empty()
+    }
+
+    /// Maps a byte offset in the translated text to the raw source. Offsets inside a
+    /// translated character map to the end of its escape.
+    pub fn raw_offset(&self, offset: usize) -> usize {
+        let i = self.escapes.partition_point(|e| e.start < offset);
+        match i.checked_sub(1).map(|i| &self.escapes[i]) {
+            Some(escape) if offset < escape.end => escape.raw_end,
+            Some(escape) => escape.raw_end + offset - escape.end,
+            None => offset,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn translation() {
+        let t = Translation::new(r#"\u0022a\uuu00e9\u005c\u005c"#);
+        assert_eq!(r#""aé\\"#, t.text());
+        assert_eq!(
+            vec![0, 6, 7, 15, 15, 21, 27],
+            [0, 1, 2, 3, 4, 5, 6].map(|o| t.raw_offset(o)).to_vec()
+        );
+        // Only backslashes preceded by an even number of backslashes start an escape.
+        assert_eq!(r"\\u0041\\A", Translation::new(r"\\u0041\\\u0041").text());
+        assert_eq!("𝄞", Translation::new(r"\uD834\uDD1E").text());
+        for code in [r"\u12", r"\u12g4", r"\x"] {
+            assert_eq!(
+                code,
+                Translation::new(code).text(),
+                "{} should not translate",
+                code
+            );
+        }
+        // Lone surrogates still map back to the whole escape.
+        let t = Translation::new(r"\uD834\u0041\uDFFF");
+        assert_eq!("\u{FFFD}A\u{FFFD}", t.text());
+        assert_eq!(
+            vec![0, 6, 12, 18],
+            [0, 3, 4, 7].map(|o| t.raw_offset(o)).to_vec()
+        );
+        assert!(!Translation::new("a\\u").is_translated());
+    }
+}
@@ -0,0 +1,169 @@
+use crate::hashmap;
+use crate::parser::{Parser, ParserContext, Token, TokenKind, Value};
+
+/// The line terminators of JLS 3.4, longest first, with the metadata flag marking each.
+const LINE_TERMINATORS: &[(&str, &str)] = &[("\r\n", "crlf"), ("\r", "cr"), ("\n", "lf")];
+
+/// Tokenizes the white space of JLS 3.6: runs of spaces, tabs and form feeds become
+/// `whitespace` tokens, and every line terminator a `line_terminator` token flagged
+/// with `crlf`, `cr` or `lf`.
+///
+/// Whitespace right before a line terminator or the end of the code is flagged as
+/// `trailing`, and indentation mixing tabs and spaces as `mixed_indent`. Other Unicode
+/// spaces are not Java whitespace and are left to the other parsers.
+pub struct WhitespaceParser;
+
+impl WhitespaceParser {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for WhitespaceParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The `line_terminator` token `code` starts with, flagged with which one it is.
+pub(crate) fn line_terminator(code: &str) -> Option<Token> {
+    LINE_TERMINATORS
+        .iter()
+        .find(|(terminator, _)| code.starts_with(terminator))
+        .map(|&(terminator, flag)| {
+            Token::with_meta(
+                TokenKind::LineTerminator,
+                terminator.len(),
+                hashmap!(String::from(flag), Value::Int(1)),
+            )
+        })
+}
+
+/// Tokenizes the white space `code` starts with, `line_start` telling whether only
+/// white space precedes it on its line. The end of `code` counts as a line end when
+/// `ends_line` is set.
+fn tokenize(code: &str, mut line_start: bool, ends_line: bool) -> Vec<Token> {
+    let mut tokens = vec![];
+    let mut offset = 0;
+    while offset < code.len() {
+        let rest = &code[offset..];
+        if let Some(token) = line_terminator(rest) {
+            offset += token.len;
+            tokens.push(token);
+            line_start = true;
+            continue;
+        }
+        let len = rest
+            .bytes()
+            .take_while(|b| matches!(b, b' ' | b'\t' | b'\x0C'))
+            .count();
+        if len == 0 {
+            break;
+        }
+        let (run, next) = rest.split_at(len);
+        let mut token = Token::whitespace(len);
+        if (ends_line && next.is_empty()) || next.starts_with(['\r', '\n']) {
+            token.add_metadata(hashmap!(String::from("trailing"), Value::Int(1)));
+        }
+        if line_start && run.contains('\t') && run.contains(' ') {
+            token.add_metadata(hashmap!(String::from("mixed_indent"), Value::Int(1)));
+        }
+        offset += len;
+        tokens.push(token);
+        line_start = false;
+    }
+    tokens
+}
+
+/// Tokenizes `run`, white space the JLS allows inside a construct another parser
+/// tokenizes, like the one between an annotation's `@` and its name.
+pub(crate) fn whitespace(run: &str) -> Vec<Token> {
+    tokenize(run, false, false)
+}
+
+impl Parser for WhitespaceParser {
+    fn parse(&self, code: &str, context: ParserContext) -> Option<Vec<Token>> {
+        let tokens = tokenize(code, context.is_line_start(), true);
+        (!tokens.is_empty()).then_some(tokens)
+    }
+
+    fn expected(&self) -> Vec<TokenKind> {
+        vec![TokenKind::Whitespace, TokenKind::LineTerminator]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{lexer::Lexer, Parser, ParserContext, Token};
+    use super::*;
+
+    fn flags(code: &str) -> Vec<(&'static str, Vec<String>)> {
+        Lexer::new()
+            .lex(code, ParserContext::new())
+            .into_iter()
+            .filter(|t| matches!(t.kind, TokenKind::Whitespace | TokenKind::LineTerminator))
+            .map(|t| {
+                let mut keys: Vec<String> = t.metadata.keys().cloned().collect();
+                keys.sort();
+                (t.name(), keys)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn whitespace_parser() {
+        let p = WhitespaceParser::new();
+        assert_eq!(
+            Some(vec![
+                Token::whitespace(2),
+                Token::with_meta(
+                    TokenKind::LineTerminator,
+                    2,
+                    hashmap!(String::from("crlf"), Value::Int(1))
+                ),
+                Token::with_meta(
+                    TokenKind::LineTerminator,
+                    1,
+                    hashmap!(String::from("lf"), Value::Int(1))
+                ),
+                Token::whitespace(1),
+            ]),
+            p.parse(" \t\r\n\n\x0Cint", ParserContext::new())
+                .map(|mut t| {
+                    t[0].metadata.clear();
+                    t
+                })
+        );
+        assert_eq!(None, p.parse("int ", ParserContext::new()));
+        // A no-break space is not Java whitespace.
+        assert_eq!(None, p.parse("\u{A0}int", ParserContext::new()));
+    }
+
+    #[test]
+    fn whitespace_flags() {
+        let keys = |keys: &[&str]| keys.iter().map(|k| k.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                ("whitespace", keys(&[])),
+                ("whitespace", keys(&["trailing"])),
+                ("line_terminator", keys(&["crlf"])),
+                ("whitespace", keys(&["mixed_indent"])),
+                ("line_terminator", keys(&["cr"])),
+                ("whitespace", keys(&["mixed_indent", "trailing"])),
+                ("line_terminator", keys(&["lf"])),
+                ("whitespace", keys(&[])),
+                ("whitespace", keys(&["trailing"])),
+            ],
+            flags("int x; \r\n\t y;\r \t\n\tz=1;\t")
+        );
+        // Tabs and spaces only mix in indentation, not inside a line.
+        assert_eq!(
+            vec![
+                ("whitespace", keys(&[])),
+                ("whitespace", keys(&[])),
+                ("whitespace", keys(&[]))
+            ],
+            flags("\tx \t= 1;")
+        );
+    }
+}